@@ -1,97 +1,44 @@
+use advent_of_code_2021::graph::Graph;
 use advent_of_code_2021::{parse_lines, read};
 use nom::bytes::complete::tag;
 use nom::character::complete::alpha1;
 use nom::sequence::separated_pair;
 use nom::IResult;
-use std::collections::HashMap;
-use std::collections::HashSet;
 
 fn line_parser(input: &str) -> IResult<&str, (&str, &str)> {
     separated_pair(alpha1, tag("-"), alpha1)(input)
 }
 
-struct Cave<'a> {
-    connections: HashSet<&'a str>,
+fn is_small(cave: &str) -> bool {
+    cave.chars().all(char::is_lowercase)
 }
 
-impl<'a> Cave<'a> {
-    fn new() -> Self {
-        Cave {
-            connections: HashSet::new(),
-        }
-    }
-}
-
-struct Map<'a> {
-    caves: HashMap<&'a str, Cave<'a>>,
-}
-
-impl<'a> Map<'a> {
-    fn new(input: &[(&'a str, &'a str)]) -> Self {
-        let mut caves: HashMap<&'a str, Cave<'a>> = HashMap::new();
-
-        for (edge_a, edge_b) in input {
-            if !caves.contains_key(edge_a) {
-                caves.insert(edge_a, Cave::new());
-            }
-            if !caves.contains_key(edge_b) {
-                caves.insert(edge_b, Cave::new());
-            }
-
-            caves.get_mut(edge_a).unwrap().connections.insert(edge_b);
-            caves.get_mut(edge_b).unwrap().connections.insert(edge_a);
-        }
-
-        Self { caves }
-    }
-}
+/// Paths from `start` to `end` visiting small caves at most once, except for a single one
+/// visited twice when `revisit` is set
+fn count_paths(input: &[(&str, &str)], revisit: bool) -> usize {
+    let map = Graph::from_undirected_pairs(input.iter().copied());
 
-fn get_paths(map: &Map, start: &str, visited: &[&str], visited_twice: bool) -> u32 {
-    let mut paths = 0;
-
-    let mut new_visited = visited.to_owned();
-    if !start.chars().all(char::is_uppercase) {
-        new_visited.push(start);
-    }
-
-    for connection in map.caves.get(start).unwrap().connections.iter() {
-        if connection == &"end" {
-            paths += 1;
-
-            continue;
-        }
-
-        if connection == &"start" {
-            // We can't return to the start
-            continue;
+    let paths = map.paths(&"start", &"end", |cave, visits| {
+        if *cave == "start" {
+            return false;
         }
 
-        if !visited.contains(connection) {
-            paths += get_paths(map, connection, &new_visited, visited_twice);
-
-            continue;
+        if !is_small(cave) || visits.get(cave).copied().unwrap_or(0) == 0 {
+            return true;
         }
 
-        if !visited_twice {
-            paths += get_paths(map, connection, &new_visited, true);
-
-            continue;
-        }
-    }
+        revisit && !visits.iter().any(|(cave, count)| is_small(cave) && *count > 1)
+    });
 
-    paths
+    paths.len()
 }
 
-fn solve_part1<'a>(input: &[(&'a str, &'a str)]) -> u32 {
-    let map = Map::new(input);
-
-    get_paths(&map, &"start", &vec![], true)
+fn solve_part1(input: &[(&str, &str)]) -> usize {
+    count_paths(input, false)
 }
 
-fn solve_part2<'a>(input: &[(&'a str, &'a str)]) -> u32 {
-    let map = Map::new(input);
-
-    get_paths(&map, &"start", &vec![], false)
+fn solve_part2(input: &[(&str, &str)]) -> usize {
+    count_paths(input, true)
 }
 
 fn main() {
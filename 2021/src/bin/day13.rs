@@ -1,4 +1,4 @@
-use advent_of_code_2021::{parse, read};
+use advent_of_code_2021::{ocr, parse, read};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::newline;
@@ -133,7 +133,13 @@ fn main() {
     let parsed_input = parse(parser, &input);
 
     println!("{}", solve_part1(&parsed_input));
-    println!("{}", solve_part2(&parsed_input));
+
+    let paper = solve_part2(&parsed_input);
+
+    match ocr::recognize_str(&paper) {
+        Ok(text) => println!("{}", text),
+        Err(error) => println!("{}{}", paper, error),
+    }
 }
 
 #[cfg(test)]
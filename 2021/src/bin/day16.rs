@@ -11,6 +11,7 @@ use nom::multi::many0;
 use nom::multi::many_m_n;
 use nom::IResult;
 use nom::Finish;
+use std::fmt;
 
 fn parser(input: &str) -> IResult<&str, Vec<u8>> {
     let (rest, groups) = terminated(many0(alt((
@@ -39,6 +40,21 @@ fn to_num(input: &[u8]) -> u32 {
     input.into_iter().rev().enumerate().filter(|(_, &val)| val == 1).map(|(i, _)| 2_u32.pow(i as u32)).sum()
 }
 
+fn to_bits(value: u64, width: usize) -> Vec<u8> {
+    (0..width).rev().map(|i| ((value >> i) & 1) as u8).collect()
+}
+
+/// Hexadecimal form of bits whose count is a multiple of 4
+fn to_hex(bits: &[u8]) -> String {
+    bits.chunks(4)
+        .map(|chunk| {
+            let digit = chunk.iter().fold(0, |digit, bit| digit * 2 + *bit as u32);
+
+            std::char::from_digit(digit, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect()
+}
+
 #[derive(Clone,Debug,Eq,PartialEq)]
 struct Packet {
     version: u8,
@@ -51,6 +67,13 @@ enum PacketData {
     Operator(Operator, Vec<Packet>),
 }
 
+/// How an operator packet announces the size of its sub-packets
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum LengthType {
+    TotalBits,
+    PacketCount,
+}
+
 #[derive(Clone,Debug,Eq,PartialEq)]
 enum Operator {
     Sum,
@@ -62,7 +85,93 @@ enum Operator {
     EqualTo,
 }
 
+impl Operator {
+    fn type_id(&self) -> u64 {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Minimum => 2,
+            Operator::Maximum => 3,
+            Operator::GreaterThan => 5,
+            Operator::LessThan => 6,
+            Operator::EqualTo => 7,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => "gt",
+            Operator::LessThan => "lt",
+            Operator::EqualTo => "eq",
+        }
+    }
+}
+
 impl Packet {
+    /// Bits of the packet, preferably announcing the size of operator contents with `length_type`.
+    /// Falls back to the other length type for contents too large for it, `None` if they're too
+    /// large for both (over 32767 bits and 2047 sub-packets).
+    fn encode(&self, length_type: LengthType) -> Option<Vec<u8>> {
+        let mut bits = to_bits(self.version as u64, 3);
+
+        match &self.data {
+            PacketData::Value(value) => {
+                bits.extend(to_bits(4, 3));
+
+                let groups = (1..).find(|groups| groups * 4 >= 64 || value >> (groups * 4) == 0).unwrap();
+
+                for group in (0..groups).rev() {
+                    bits.push(if group == 0 { 0 } else { 1 });
+                    bits.extend(to_bits(value >> (group * 4), 4));
+                }
+            },
+            PacketData::Operator(operator, packets) => {
+                bits.extend(to_bits(operator.type_id(), 3));
+
+                let content: Vec<u8> = packets.iter()
+                    .map(|packet| packet.encode(length_type))
+                    .collect::<Option<Vec<_>>>()?
+                    .concat();
+
+                let fits_bits = content.len() < 1 << 15;
+                let fits_count = packets.len() < 1 << 11;
+
+                match length_type {
+                    LengthType::TotalBits if fits_bits => {
+                        bits.push(0);
+                        bits.extend(to_bits(content.len() as u64, 15));
+                    },
+                    _ if fits_count => {
+                        bits.push(1);
+                        bits.extend(to_bits(packets.len() as u64, 11));
+                    },
+                    _ if fits_bits => {
+                        bits.push(0);
+                        bits.extend(to_bits(content.len() as u64, 15));
+                    },
+                    _ => return None,
+                }
+
+                bits.extend(content);
+            },
+        }
+
+        Some(bits)
+    }
+
+    /// Transmission of the packet, padded with zeros to a whole number of bytes
+    fn to_hex(&self, length_type: LengthType) -> Option<String> {
+        let mut bits = self.encode(length_type)?;
+
+        bits.resize(bits.len().div_ceil(8) * 8, 0);
+
+        Some(to_hex(&bits))
+    }
+
     fn sum_version(&self) -> u32 {
         self.version as u32 + match &self.data {
             PacketData::Value(_) => 0,
@@ -86,6 +195,24 @@ impl Packet {
     }
 }
 
+/// S-expression of the computation, like `(sum (lt 5 15) 7)`
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.data {
+            PacketData::Value(value) => write!(f, "{}", value),
+            PacketData::Operator(operator, packets) => {
+                write!(f, "({}", operator.name())?;
+
+                for packet in packets {
+                    write!(f, " {}", packet)?;
+                }
+
+                write!(f, ")")
+            },
+        }
+    }
+}
+
 fn parse_packet(input: &[u8]) -> IResult<&[u8], Packet> {
     let (input, version) = take(3_u8)(input)?;
 
@@ -165,6 +292,18 @@ fn main() {
 
     println!("{}", solve_part1(&parsed_input));
     println!("{}", solve_part2(&parsed_input));
+
+    if std::env::args().nth(1).as_deref() == Some("--print") {
+        let (_, packet) = parse_packet(&parsed_input).finish().unwrap();
+
+        println!("{}", packet);
+        for length_type in [LengthType::TotalBits, LengthType::PacketCount] {
+            match packet.to_hex(length_type) {
+                Some(hex) => println!("{}", hex),
+                None => println!("Too large to encode"),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -173,7 +312,9 @@ mod tests {
     use super::Packet;
     use super::PacketData;
     use super::Operator;
+    use super::LengthType;
     use super::parser;
+    use super::parse_packet;
     use super::to_num;
     use super::parse_value_data;
     use super::parse_operator_data;
@@ -212,6 +353,108 @@ mod tests {
         );
     }
 
+    fn decode(hex: &str) -> Packet {
+        let (_, bits) = parser(&format!("{}\n", hex)).finish().unwrap();
+
+        parse_packet(&bits).finish().unwrap().1
+    }
+
+    fn value(version: u8, value: u64) -> Packet {
+        Packet { version, data: PacketData::Value(value) }
+    }
+
+    fn operator(version: u8, operator: Operator, packets: Vec<Packet>) -> Packet {
+        Packet { version, data: PacketData::Operator(operator, packets) }
+    }
+
+    /// Pseudo random packet tree, driven by a linear congruential generator
+    fn random_packet(seed: &mut u64, depth: usize) -> Packet {
+        let mut next = |bound: u64| {
+            *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+
+            (*seed >> 33) % bound
+        };
+
+        let version = next(8) as u8;
+
+        if depth == 0 || next(3) == 0 {
+            let width = next(64) as u32;
+
+            let bits = next(1 << 31) << 33 | next(1 << 31) << 2 | next(4);
+
+            return value(version, bits >> (63 - width));
+        }
+
+        let operators = [Operator::Sum, Operator::Product, Operator::Minimum, Operator::Maximum, Operator::GreaterThan, Operator::LessThan, Operator::EqualTo];
+        let operator = operators[next(7) as usize].clone();
+        let count = match operator {
+            Operator::GreaterThan | Operator::LessThan | Operator::EqualTo => 2,
+            _ => 1 + next(4) as usize,
+        };
+
+        let packets = (0..count).map(|_| random_packet(seed, depth - 1)).collect();
+
+        self::operator(version, operator, packets)
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(value(6, 2021).to_hex(LengthType::TotalBits).unwrap(), "D2FE28");
+        assert_eq!(
+            operator(1, Operator::LessThan, vec![value(6, 10), value(2, 20)]).to_hex(LengthType::TotalBits).unwrap(),
+            "38006F45291200",
+        );
+        assert_eq!(
+            operator(7, Operator::Maximum, vec![value(2, 1), value(4, 2), value(1, 3)]).to_hex(LengthType::PacketCount).unwrap(),
+            "EE00D40C823060",
+        );
+    }
+
+    #[test]
+    fn test_encode_large() {
+        // Too many sub-packets to count them, but few enough bits to measure them
+        let many = operator(0, Operator::Sum, vec![value(0, 0); 2048]);
+
+        assert_eq!(many.encode(LengthType::PacketCount).unwrap()[6], 0);
+        assert_eq!(decode(&many.to_hex(LengthType::PacketCount).unwrap()), many);
+
+        // Too many bits to measure them, but few enough sub-packets to count them
+        let long = operator(0, Operator::Sum, vec![value(0, u64::MAX); 2000]);
+
+        assert_eq!(long.encode(LengthType::TotalBits).unwrap()[6], 1);
+        assert_eq!(decode(&long.to_hex(LengthType::TotalBits).unwrap()), long);
+
+        assert_eq!(operator(0, Operator::Sum, vec![value(0, 0); 3000]).encode(LengthType::TotalBits), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(decode("9C0141080250320F1802104A08").to_string(), "(eq (sum 1 3) (product 2 2))");
+        assert_eq!(
+            operator(0, Operator::Sum, vec![operator(0, Operator::LessThan, vec![value(0, 5), value(0, 15)]), value(0, 7)]).to_string(),
+            "(sum (lt 5 15) 7)",
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for hex in ["8A004A801A8002F478", "620080001611562C8802118E34", "C0015000016115A2E0802F182340", "A0016C880162017C3686B18A3D4780"] {
+            let packet = decode(hex);
+
+            assert_eq!(decode(&packet.to_hex(LengthType::TotalBits).unwrap()), packet);
+            assert_eq!(decode(&packet.to_hex(LengthType::PacketCount).unwrap()), packet);
+        }
+
+        let mut seed = 42;
+
+        for _ in 0..200 {
+            let packet = random_packet(&mut seed, 4);
+
+            assert_eq!(decode(&packet.to_hex(LengthType::TotalBits).unwrap()), packet);
+            assert_eq!(decode(&packet.to_hex(LengthType::PacketCount).unwrap()), packet);
+        }
+    }
+
     #[test]
     fn test_solve_part_1() {
         let (_, input) = parser("A0016C880162017C3686B18A3D4780\n").finish().unwrap();
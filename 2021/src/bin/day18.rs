@@ -5,52 +5,24 @@ use nom::bytes::complete::tag;
 use nom::character::complete::u8;
 use nom::combinator::map;
 use nom::combinator::value;
+use nom::combinator::all_consuming;
+use nom::combinator::map_parser;
+use nom::combinator::recognize;
 use nom::multi::many0;
 use nom::sequence::tuple;
 use nom::Finish;
 use nom::IResult;
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-struct Pair {
-    left: Value,
-    right: Value,
-}
-
-#[derive(Clone, Debug, Eq, PartialEq)]
-enum Value {
-    Literal(u8),
-    Pair(Box<Pair>),
-}
-
-impl Pair {
-    fn magnitude(&self) -> u32 {
-        self.left.magnitude() * 3 + self.right.magnitude() * 2
-    }
-}
-
-impl Value {
-    fn magnitude(&self) -> u32 {
-        match self {
-            Value::Literal(value) => *value as u32,
-            Value::Pair(pair) => pair.magnitude(),
-        }
-    }
-}
-
-fn value_parser(input: &str) -> IResult<&str, Value> {
+fn element_parser(input: &str) -> IResult<&str, ()> {
     alt((
-        map(u8, |value| Value::Literal(value)),
-        map(pair_parser, |value| Value::Pair(Box::new(value))),
+        value((), u8),
+        value((), tuple((tag("["), element_parser, tag(","), element_parser, tag("]")))),
     ))(input)
 }
 
-fn pair_parser(input: &str) -> IResult<&str, Pair> {
-    let (input, (_, left, _, right, _)) =
-        tuple((tag("["), value_parser, tag(","), value_parser, tag("]")))(input)?;
-
-    Ok((input, Pair { left, right }))
-}
-
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum Token {
     Left,
@@ -77,22 +49,8 @@ fn line_parser(input: &str) -> IResult<&str, Vec<Token>> {
     )))(input)
 }
 
-fn reduce(input: &[Token]) -> Vec<Token> {
-    let mut tokens = input.to_owned();
-
-    loop {
-        if try_explode(&mut tokens) {
-            continue;
-        }
-
-        if try_split(&mut tokens) {
-            continue;
-        }
-
-        break;
-    }
-
-    tokens
+fn number_parser(input: &str) -> IResult<&str, SnailfishNumber> {
+    map(map_parser(recognize(element_parser), line_parser), |tokens| SnailfishNumber { tokens })(input)
 }
 
 fn try_explode(tokens: &mut Vec<Token>) -> bool {
@@ -162,136 +120,228 @@ fn try_split(tokens: &mut Vec<Token>) -> bool {
     false
 }
 
-fn add(left: &[Token], rigth: &[Token]) -> Vec<Token> {
-    reduce(
-        &[
-            &[Token::Left],
-            left,
-            &[Token::Comma],
-            rigth,
-            &[Token::Right],
-        ]
-        .concat(),
-    )
+/// What a reduction step did to the number
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Action {
+    Explode,
+    Split,
 }
 
-fn stringify(tokens: &[Token]) -> String {
-    tokens
-        .iter()
-        .map(|token| match token {
-            Token::Left => "[".to_string(),
-            Token::Right => "]".to_string(),
-            Token::Comma => ",".to_string(),
-            Token::Value(value) => value.to_string(),
-        })
-        .collect()
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct SnailfishNumber {
+    tokens: Vec<Token>,
+}
+
+impl SnailfishNumber {
+    /// Sum of the numbers from left to right, `None` if there are none as there's no zero
+    /// snailfish number
+    fn sum<'a>(numbers: impl IntoIterator<Item = &'a Self>) -> Option<Self> {
+        numbers.into_iter().cloned().reduce(|left, right| left + right)
+    }
+
+    /// Pair of the two numbers, without reducing it
+    fn pair(&self, other: &Self) -> Self {
+        Self {
+            tokens: [&[Token::Left], &self.tokens[..], &[Token::Comma], &other.tokens[..], &[Token::Right]].concat(),
+        }
+    }
+
+    /// Steps reducing the number, along with the number after each of them
+    fn reduction(&self) -> Reduction {
+        Reduction { number: self.clone() }
+    }
+
+    fn reduce(&self) -> Self {
+        self.reduction().last().map_or_else(|| self.clone(), |(_, number)| number)
+    }
+
+    fn magnitude(&self) -> u32 {
+        let mut stack = Vec::new();
+
+        for token in &self.tokens {
+            match token {
+                Token::Value(value) => stack.push(*value as u32),
+                Token::Right => {
+                    let right = stack.pop().unwrap();
+                    let left = stack.pop().unwrap();
+
+                    stack.push(left * 3 + right * 2);
+                },
+                _ => (),
+            }
+        }
+
+        stack.pop().unwrap()
+    }
+}
+
+struct Reduction {
+    number: SnailfishNumber,
 }
 
-fn magnitude(tokens: &[Token]) -> u32 {
-    let (_, pair) = pair_parser(&stringify(tokens)).finish().unwrap();
+impl Iterator for Reduction {
+    type Item = (Action, SnailfishNumber);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = if try_explode(&mut self.number.tokens) {
+            Action::Explode
+        } else if try_split(&mut self.number.tokens) {
+            Action::Split
+        } else {
+            return None;
+        };
 
-    pair.magnitude()
+        Some((action, self.number.clone()))
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = nom::error::Error<String>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match all_consuming(number_parser)(s).finish() {
+            Ok((_, number)) => Ok(number),
+            Err(error) => Err(nom::error::Error::new(error.input.to_owned(), error.code)),
+        }
+    }
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in &self.tokens {
+            match token {
+                Token::Left => write!(f, "[")?,
+                Token::Right => write!(f, "]")?,
+                Token::Comma => write!(f, ",")?,
+                Token::Value(value) => write!(f, "{}", value)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Add for &SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.pair(rhs).reduce()
+    }
 }
 
-fn solve_part1(input: &[Vec<Token>]) -> u32 {
-    let result = input
-        .to_owned()
-        .into_iter()
-        .reduce(|left, right| add(&left, &right))
-        .unwrap();
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
 
-    magnitude(&result)
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+fn solve_part1(input: &[SnailfishNumber]) -> Option<u32> {
+    SnailfishNumber::sum(input).map(|sum| sum.magnitude())
 }
 
-fn solve_part2(input: &[Vec<Token>]) -> u32 {
+fn solve_part2(input: &[SnailfishNumber]) -> Option<u32> {
     input
         .iter()
         .cartesian_product(input)
-        .map(|(left, rigth)| magnitude(&add(left, rigth)))
+        .map(|(left, rigth)| (left + rigth).magnitude())
         .max()
-        .unwrap()
 }
 
 fn main() {
     let input = read(18);
 
-    let parsed_input = parse_lines(line_parser, &input);
+    let parsed_input = parse_lines(number_parser, &input);
+
+    let (Some(part1), Some(part2)) = (solve_part1(&parsed_input), solve_part2(&parsed_input)) else {
+        println!("No snailfish numbers to add");
+        return;
+    };
+
+    println!("{}", part1);
+    println!("{}", part2);
+
+    if std::env::args().nth(1).as_deref() == Some("--trace") {
+        let sum = parsed_input.iter().skip(1).fold(parsed_input[0].clone(), |left, right| {
+            let pair = left.pair(right);
+
+            println!("after addition: {}", pair);
+
+            for (action, number) in pair.reduction() {
+                match action {
+                    Action::Explode => println!("after explode:  {}", number),
+                    Action::Split => println!("after split:    {}", number),
+                }
+            }
+
+            pair.reduce()
+        });
 
-    println!("{}", solve_part1(&parsed_input));
-    println!("{}", solve_part2(&parsed_input));
+        println!("= {}", sum);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::add;
-    use super::line_parser;
-    use super::magnitude;
-    use super::pair_parser;
-    use super::reduce;
-    use super::stringify;
-    use super::Pair;
-    use super::Value;
-    use nom::Finish;
+    use super::Action;
+    use super::SnailfishNumber;
+    use super::solve_part1;
+
+    fn number(input: &str) -> SnailfishNumber {
+        input.parse().unwrap()
+    }
 
     #[test]
-    fn test_pair_parser() {
-        assert_eq!(
-            pair_parser("[9,[8,7]]"),
-            Ok((
-                "",
-                Pair {
-                    left: Value::Literal(9),
-                    right: Value::Pair(Box::new(Pair {
-                        left: Value::Literal(8),
-                        right: Value::Literal(7),
-                    }))
-                }
-            ))
-        );
+    fn test_from_str() {
+        assert_eq!(number("[9,[8,7]]").to_string(), "[9,[8,7]]");
+        assert!("[9,[8,7]".parse::<SnailfishNumber>().is_err());
+        assert!("[9,[8,7]]x".parse::<SnailfishNumber>().is_err());
+        assert!("[9,8,7]".parse::<SnailfishNumber>().is_err());
     }
 
     #[test]
     fn test_reduce_1() {
-        let (_, input) = line_parser("[[[[[9,8],1],2],3],4]").finish().unwrap();
-        let (_, expected) = line_parser("[[[[0,9],2],3],4]").finish().unwrap();
-
-        assert_eq!(reduce(&input), expected);
+        assert_eq!(number("[[[[[9,8],1],2],3],4]").reduce(), number("[[[[0,9],2],3],4]"));
     }
 
     #[test]
     fn test_reduce_2() {
-        let (_, input) = line_parser("[11,0]").finish().unwrap();
-        let (_, expected) = line_parser("[[5,6],0]").finish().unwrap();
-
-        assert_eq!(reduce(&input), expected);
+        assert_eq!(number("[11,0]").reduce(), number("[[5,6],0]"));
     }
 
     #[test]
     fn test_add() {
-        let (_, left) = line_parser("[[[[4,3],4],4],[7,[[8,4],9]]]")
-            .finish()
-            .unwrap();
-        let (_, rigth) = line_parser("[1,1]").finish().unwrap();
-        let (_, expected) = line_parser("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
-            .finish()
-            .unwrap();
-
-        assert_eq!(add(&left, &rigth), expected);
+        assert_eq!(number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]"), number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"));
     }
 
     #[test]
-    fn test_stringify() {
-        let (_, input) = line_parser("[[[[4,3],4],4],[7,[[8,4],9]]]")
-            .finish()
-            .unwrap();
+    fn test_reduction() {
+        let pair = number("[[[[4,3],4],4],[7,[[8,4],9]]]").pair(&number("[1,1]"));
+
+        assert_eq!(pair.to_string(), "[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        assert_eq!(pair.reduction().map(|(action, number)| (action, number.to_string())).collect::<Vec<_>>(), vec![
+            (Action::Explode, "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]".to_owned()),
+            (Action::Explode, "[[[[0,7],4],[15,[0,13]]],[1,1]]".to_owned()),
+            (Action::Split, "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]".to_owned()),
+            (Action::Split, "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]".to_owned()),
+            (Action::Explode, "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]".to_owned()),
+        ]);
+    }
 
-        assert_eq!(stringify(&input), "[[[[4,3],4],4],[7,[[8,4],9]]]");
+    #[test]
+    fn test_sum() {
+        let numbers = vec![number("[1,1]"), number("[2,2]"), number("[3,3]"), number("[4,4]"), number("[5,5]"), number("[6,6]")];
+
+        assert_eq!(SnailfishNumber::sum(&numbers), Some(number("[[[[5,0],[7,4]],[5,5]],[6,6]]")));
+        assert_eq!(solve_part1(&numbers), Some(number("[[[[5,0],[7,4]],[5,5]],[6,6]]").magnitude()));
+        assert_eq!(SnailfishNumber::sum(&[]), None);
+        assert_eq!(solve_part1(&[]), None);
     }
 
     #[test]
     fn test_magnitude() {
-        let (_, input) = line_parser("[[1,2],[[3,4],5]]").finish().unwrap();
-
-        assert_eq!(magnitude(&input), 143);
+        assert_eq!(number("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(), 3488);
     }
 }
@@ -1,650 +1,499 @@
 use advent_of_code_2021::{parse, read};
-use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::combinator::value;
+use nom::bytes::complete::take_till1;
+use nom::character::complete::newline;
+use nom::combinator::map_res;
+use nom::multi::many1;
+use nom::sequence::terminated;
 use nom::IResult;
-use std::collections::HashMap;
-use std::collections::HashSet;
-
-fn parse_type(input: &str) -> IResult<&str, Type> {
-    alt((
-        value(Type::Amber, tag("A")),
-        value(Type::Bronze, tag("B")),
-        value(Type::Copper, tag("C")),
-        value(Type::Desert, tag("D")),
-    ))(input)
-}
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::ops::RangeInclusive;
 
-fn parser(input: &str) -> IResult<&str, Map<2>> {
-    // Line 1
-    let (input, _) = tag("#############\n")(input)?;
-    // Line 2
-    let (input, _) = tag("#...........#\n")(input)?;
-    // Line 3
-    let (input, _) = tag("###")(input)?;
-    let (input, t11) = parse_type(input)?;
-    let (input, _) = tag("#")(input)?;
-    let (input, t12) = parse_type(input)?;
-    let (input, _) = tag("#")(input)?;
-    let (input, t13) = parse_type(input)?;
-    let (input, _) = tag("#")(input)?;
-    let (input, t14) = parse_type(input)?;
-    let (input, _) = tag("###\n")(input)?;
-    // Line 4
-    let (input, _) = tag("  #")(input)?;
-    let (input, t21) = parse_type(input)?;
-    let (input, _) = tag("#")(input)?;
-    let (input, t22) = parse_type(input)?;
-    let (input, _) = tag("#")(input)?;
-    let (input, t23) = parse_type(input)?;
-    let (input, _) = tag("#")(input)?;
-    let (input, t24) = parse_type(input)?;
-    let (input, _) = tag("#\n")(input)?;
-    // Line 5
-    let (input, _) = tag("  #########\n")(input)?;
-
-    Ok((input, Map {
-        corridor: [None, None, None, None, None, None, None],
-        rooms: [
-            [Some(t11), Some(t21)],
-            [Some(t12), Some(t22)],
-            [Some(t13), Some(t23)],
-            [Some(t14), Some(t24)],
-        ]
-    }))
-}
+/// Amphipods are identified by the index of their destination room, `A` going to the first one
+type Amphipod = u8;
+
+/// Most rooms supported, the energy of the costliest amphipod (10^15) leaving enough margin in a
+/// `u64` for the distances walked
+const MAX_ROOMS: usize = 16;
 
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
-struct Map<const SIZE: usize> {
-    corridor: [Option<Type>; 7],
-    rooms: [[Option<Type>; SIZE]; 4],
+fn energy(amphipod: Amphipod) -> u64 {
+    10u64.pow(amphipod as u32)
 }
 
-fn d(t: &Option<Type>) -> char {
-    match t {
+fn name(amphipod: Option<Amphipod>) -> char {
+    match amphipod {
         None => '.',
-        Some(u) => match u {
-            Type::Amber => 'A',
-            Type::Bronze => 'B',
-            Type::Copper => 'C',
-            Type::Desert => 'D',
-        }
+        Some(amphipod) => (b'A' + amphipod) as char,
     }
 }
 
-impl<const SIZE: usize> std::fmt::Display for Map<SIZE> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "#############")?;
-        writeln!(f, "#{}{}.{}.{}.{}.{}{}#", d(&self.corridor[0]), d(&self.corridor[1]), d(&self.corridor[2]), d(&self.corridor[3]), d(&self.corridor[4]), d(&self.corridor[5]), d(&self.corridor[6]))?;
-
-        for i in 0..SIZE {
-            if i == 0 {
-                write!(f, "##")?;
-            } else {
-                write!(f, "  ")?;
-            }
-
-            write!(f, "#{}#{}#{}#{}#", d(&self.rooms[0][i]), d(&self.rooms[1][i]), d(&self.rooms[2][i]), d(&self.rooms[3][i]))?;
-
-            if i == 0 {
-                writeln!(f, "##")?;
-            } else {
-                writeln!(f, "")?;
-            }
-        }
+/// Cells traversed when walking the hallway from `from` (excluded) to `to` (included)
+fn steps(from: usize, to: usize) -> RangeInclusive<usize> {
+    if from < to { from + 1..=to } else { to..=from - 1 }
+}
 
-        write!(f, "  #########")
-    }
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+struct Burrow {
+    hallway: Vec<Option<Amphipod>>,
+    /// Position in the hallway of the entrance of each room
+    doors: Vec<usize>,
+    /// Content of each room, top first
+    rooms: Vec<Vec<Option<Amphipod>>>,
 }
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
-enum Type {
-    Amber,
-    Bronze,
-    Copper,
-    Desert,
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Location {
+    Hallway(usize),
+    Room(usize, usize),
 }
 
-fn move_energy(amphipod: &Type) -> u32 {
-    match amphipod {
-        Type::Amber => 1,
-        Type::Bronze => 10,
-        Type::Copper => 100,
-        Type::Desert => 1000,
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Hallway(position) => write!(f, "hallway {}", position),
+            Location::Room(room, depth) => write!(f, "room {} depth {}", room, depth),
+        }
     }
 }
 
-static DESTINATIONS: [Type; 4] = [Type::Amber, Type::Bronze, Type::Copper, Type::Desert];
-
-/*
-#############
-#01.2.3.4.56#
-###0#.#.#.###
-  #1#.#.#.#
-  #########
-*/
-fn outsides(room: usize) -> [usize; 2] {
-    [room + 1, room + 2]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Move {
+    amphipod: Amphipod,
+    from: Location,
+    to: Location,
+    energy: u64,
 }
 
-fn move_cost(room: usize, depth: usize, corridor: usize, cost: u32) -> u32 {
-    let outsides = outsides(room);
-
-    let mut move_cost = 0;
-    let mut position = corridor;
-
-    if position == 0 {
-        position += 1;
-        move_cost += cost;
-    }
-
-    while position < outsides[0] {
-        position += 1;
-        move_cost += 2 * cost;
-    }
-
-    if position == 6 {
-        position -= 1;
-        move_cost += cost;
-    }
-
-    while position > outsides[1] {
-        position -= 1;
-        move_cost += 2 * cost;
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} from {} to {} ({} energy)", name(Some(self.amphipod)), self.from, self.to, self.energy)
     }
+}
 
-    move_cost += (depth as u32 + 2) * cost;
+impl Burrow {
+    fn from_lines(lines: Vec<&str>) -> Result<Self, &'static str> {
+        let is_cell = |c: char| c == '.' || c.is_ascii_uppercase();
 
-    move_cost
-}
+        let hallway_line = lines.get(1).ok_or("Missing hallway")?;
+        let start = hallway_line.find(is_cell).ok_or("Empty hallway")?;
+        let end = hallway_line.rfind(is_cell).unwrap() + 1;
 
-fn move_out<const SIZE: usize>(map: &Map<SIZE>, room: usize, depth: usize, corridor: usize) -> (Map<SIZE>, u32) {
-    let mut new = map.clone();
+        let cell = |c: char| -> Result<Option<Amphipod>, &'static str> {
+            match c {
+                '.' => Ok(None),
+                'A'..='Z' => Ok(Some(c as u8 - b'A')),
+                _ => Err("Invalid cell"),
+            }
+        };
 
-    let am = map.rooms[room][depth].to_owned().unwrap();
+        let hallway = hallway_line[start..end].chars().map(cell).collect::<Result<Vec<_>, _>>()?;
 
-    debug_assert!(map.corridor[corridor] == None, "Trying to move to an non-empty space");
+        let mut doors: Vec<usize> = Vec::new();
+        let mut rooms: Vec<Vec<Option<Amphipod>>> = Vec::new();
 
-    let cost = move_energy(&am);
+        for line in &lines[2..] {
+            for (column, c) in line.char_indices().filter(|&(_, c)| is_cell(c)) {
+                if column < start || column >= end {
+                    return Err("Room outside of the hallway");
+                }
 
-    new.corridor[corridor] = Some(am);
-    new.rooms[room][depth] = None;
+                let door = column - start;
 
-    (new, move_cost(room, depth, corridor, cost))
-}
+                match doors.iter().position(|&other| other == door) {
+                    Some(room) => rooms[room].push(cell(c)?),
+                    None => {
+                        doors.push(door);
+                        rooms.push(vec![cell(c)?]);
+                    },
+                }
+            }
+        }
 
-fn move_in<const SIZE: usize>(map: &Map<SIZE>, corridor: usize, room: usize, depth: usize) -> (Map<SIZE>, u32) {
-    let mut new = map.clone();
+        let depth = rooms.first().ok_or("No rooms")?.len();
 
-    let am = map.corridor[corridor].to_owned().unwrap();
+        if rooms.len() > MAX_ROOMS {
+            return Err("Too many rooms");
+        }
 
-    debug_assert!(map.rooms[room][depth] == None, "Trying to move to an non-empty space");
+        if rooms.iter().any(|room| room.len() != depth) {
+            return Err("Rooms of different depths");
+        }
 
-    let cost = move_energy(&am);
+        let mut counts = vec![0; rooms.len()];
 
-    new.corridor[corridor] = None;
-    new.rooms[room][depth] = Some(am);
+        for amphipod in hallway.iter().chain(rooms.iter().flatten()).flatten() {
+            *counts.get_mut(*amphipod as usize).ok_or("Amphipod without a room")? += 1;
+        }
 
-    (new, move_cost(room, depth, corridor, cost))
-}
+        if counts.iter().any(|&count| count != depth) {
+            return Err("Rooms can't be filled exactly");
+        }
 
-fn move_outside<const SIZE: usize>(moves: &mut Vec<(Map<SIZE>, u32)>, map: &Map<SIZE>, room: usize, start: usize) {
-    let [mut left, mut right] = outsides(room);
+        Ok(Self { hallway, doors, rooms })
+    }
 
-    while None == map.corridor[left] {
-        moves.push(move_out(map, room, start, left));
+    fn depth(&self) -> usize {
+        self.rooms[0].len()
+    }
 
-        if left == 0 {
-            break;
-        }
+    fn is_clear(&self, cells: RangeInclusive<usize>) -> bool {
+        self.hallway[cells].iter().all(Option::is_none)
+    }
 
-        left -= 1;
+    /// Number of amphipods at the bottom of the room which are already in their destination
+    fn settled(&self, room: usize) -> usize {
+        self.rooms[room].iter().rev().take_while(|&&amphipod| amphipod == Some(room as Amphipod)).count()
     }
 
-    while None == map.corridor[right] {
-        moves.push(move_out(map, room, start, right));
+    /// Depth an amphipod would end up at when entering its destination room, if it can
+    fn entry(&self, room: usize) -> Option<usize> {
+        let free = self.rooms[room].iter().take_while(|amphipod| amphipod.is_none()).count();
 
-        if right == 6 {
-            break;
+        if free > 0 && self.settled(room) == self.depth() - free {
+            Some(free - 1)
+        } else {
+            None
         }
-
-        right += 1;
     }
-}
 
-fn move_inside<const SIZE: usize>(moves: &mut Vec<(Map<SIZE>, u32)>, map: &Map<SIZE>, room: usize, depth: usize) {
-    let [mut left, mut right] = outsides(room);
+    fn is_done(&self) -> bool {
+        (0..self.rooms.len()).all(|room| self.settled(room) == self.depth())
+    }
 
-    loop {
-        if None == map.corridor[left] {
-            if left == 0 {
-                break;
-            } else {
-                left -= 1;
+    fn apply(&self, movement: &Move) -> Self {
+        let mut next = self.clone();
 
-                continue;
-            }
+        match movement.from {
+            Location::Hallway(position) => next.hallway[position] = None,
+            Location::Room(room, depth) => next.rooms[room][depth] = None,
         }
 
-        if map.corridor[left] != Some(DESTINATIONS[room]) {
-            break;
+        match movement.to {
+            Location::Hallway(position) => next.hallway[position] = Some(movement.amphipod),
+            Location::Room(room, depth) => next.rooms[room][depth] = Some(movement.amphipod),
         }
 
-        moves.push(move_in(map, left, room, depth));
-
-        break;
+        next
     }
 
-    loop {
-        if None == map.corridor[right] {
-            if right == 6 {
-                break;
-            } else {
-                right += 1;
+    fn moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
 
-                continue;
+        let mut push = |amphipod: Amphipod, from: Location, to: Location, distance: usize| {
+            moves.push(Move { amphipod, from, to, energy: distance as u64 * energy(amphipod) });
+        };
+
+        for (position, amphipod) in self.hallway.iter().enumerate() {
+            let Some(amphipod) = *amphipod else { continue };
+            let room = amphipod as usize;
+            let door = self.doors[room];
+
+            if let Some(depth) = self.entry(room) {
+                if self.is_clear(steps(position, door)) {
+                    push(amphipod, Location::Hallway(position), Location::Room(room, depth), position.abs_diff(door) + depth + 1);
+                }
             }
         }
 
-        if map.corridor[right] != Some(DESTINATIONS[room]) {
-            break;
-        }
+        for (room, content) in self.rooms.iter().enumerate() {
+            let Some(top) = content.iter().position(Option::is_some) else { continue };
 
-        moves.push(move_in(map, right, room, depth));
+            if self.settled(room) == self.depth() - top {
+                continue;
+            }
 
-        break;
-    }
-}
+            let amphipod = content[top].unwrap();
+            let door = self.doors[room];
+            let from = Location::Room(room, top);
 
-/*
-#############
-#01.2.3.4.56#
-###0#.#.#.###
-  #1#.#.#.#
-  #########
-*/
-fn moves<const SIZE: usize>(map: &Map<SIZE>) -> Vec<(Map<SIZE>, u32)> {
-    let mut moves = Vec::new();
-
-    for room in 0..4 {
-        for i in 0..SIZE {
-            if &None == &map.rooms[room][i] {
-                // Nothing to move
+            if self.hallway[door].is_some() {
                 continue;
             }
 
-            if (i..SIZE).all(|j| &map.rooms[room][j] == &Some(DESTINATIONS[room])) {
-                // Everything is good, we shouldn't move anything
-                break;
-            }
+            let target = amphipod as usize;
 
-            move_outside(&mut moves, map, room, i);
+            if let Some(depth) = self.entry(target) {
+                if target != room && self.is_clear(steps(door, self.doors[target])) {
+                    push(amphipod, from, Location::Room(target, depth), top + 1 + door.abs_diff(self.doors[target]) + depth + 1);
+                }
+            }
 
-            break;
+            for position in 0..self.hallway.len() {
+                if !self.doors.contains(&position) && self.is_clear(steps(door, position)) {
+                    push(amphipod, from, Location::Hallway(position), top + 1 + door.abs_diff(position));
+                }
+            }
         }
-    }
 
-    for room in 0..4 {
-        for i in 0..SIZE {
-            if &None != &map.rooms[room][i] {
-                // It's full !
-                break;
-            }
+        moves
+    }
 
-            // Everything under us is good
-            if (i+1..SIZE).all(|j| &map.rooms[room][j] == &Some(DESTINATIONS[room])) {
-                move_inside(&mut moves, map, room, i);
+    /// Energy needed if amphipods could walk through each other, which never overestimates
+    fn lower_bound(&self) -> u64 {
+        let mut bound = 0;
 
-                break;
+        for (position, amphipod) in self.hallway.iter().enumerate() {
+            if let Some(amphipod) = *amphipod {
+                bound += (position.abs_diff(self.doors[amphipod as usize]) + 1) as u64 * energy(amphipod);
             }
         }
-    }
 
-    moves
-}
+        for (room, content) in self.rooms.iter().enumerate() {
+            let unsettled = self.depth() - self.settled(room);
 
-fn is_win<const SIZE: usize>(map: &Map<SIZE>) -> bool {
-    if map.corridor != [None, None, None, None, None, None, None] {
-        return false;
-    }
+            for (depth, amphipod) in content.iter().enumerate().take(unsettled) {
+                if let Some(amphipod) = *amphipod {
+                    let target = amphipod as usize;
+                    let walk = if target == room { 2 } else { self.doors[room].abs_diff(self.doors[target]) };
 
-    for room in 0..4 {
-        for i in 0..SIZE {
-            if map.rooms[room][i] != Some(DESTINATIONS[room]) {
-                return false;
+                    bound += (depth + 1 + walk + 1) as u64 * energy(amphipod);
+                }
             }
+
+            // Every amphipod was counted as stopping at the top of its room
+            bound += (unsettled * unsettled.saturating_sub(1) / 2) as u64 * energy(room as Amphipod);
         }
+
+        bound
     }
 
-    return true;
-}
+    /// Second part of the puzzle, with two more rows in the rooms. The rows are only defined for
+    /// the layout of the puzzle, four rooms two deep, `None` for any other one.
+    fn unfold(&self) -> Option<Self> {
+        let extra = [[3, 2, 1, 0], [3, 1, 0, 2]];
 
-fn all_min<'a, const SIZE: usize>(states: &HashMap<Map<SIZE>, u32>, unvisited: &HashSet<Map<SIZE>>) -> (Vec<Map<SIZE>>, u32) {
-    let mut cost = &u32::MAX;
-    let mut maps = Vec::new();
+        if self.rooms.len() != 4 || self.depth() != 2 {
+            return None;
+        }
 
-    for map in unvisited {
-        let map_cost = states.get(map).unwrap();
+        let mut unfolded = self.clone();
 
-        if map_cost < cost {
-            maps = Vec::new();
-            cost = map_cost;
+        for (room, content) in unfolded.rooms.iter_mut().enumerate() {
+            content.splice(1..1, extra.iter().map(|row| Some(row[room])));
         }
 
-        if map_cost == cost {
-            maps.push(map);
-        }
+        Some(unfolded)
     }
+}
 
-    (maps.into_iter().map(|map| map.to_owned()).collect(), *cost)
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.hallway.len() + 2;
+        let first = *self.doors.iter().min().unwrap();
+        let last = *self.doors.iter().max().unwrap();
+
+        writeln!(f, "{}", "#".repeat(width))?;
+        writeln!(f, "#{}#", self.hallway.iter().map(|&amphipod| name(amphipod)).collect::<String>())?;
+
+        for depth in 0..self.depth() {
+            let line = (0..width)
+                .map(|column| match self.doors.iter().position(|&door| door + 1 == column) {
+                    Some(room) => name(self.rooms[room][depth]),
+                    None if depth == 0 || (first..=last + 2).contains(&column) => '#',
+                    None => ' ',
+                })
+                .collect::<String>();
+
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        write!(f, "{}{}", " ".repeat(first), "#".repeat(last - first + 3))
+    }
 }
 
-fn solve<const SIZE: usize>(input: &Map<SIZE>) -> u32 {
-    let mut states = HashMap::new();
-    let mut unvisited = HashSet::new();
+fn parser(input: &str) -> IResult<&str, Burrow> {
+    map_res(many1(terminated(take_till1(|c| c == '\n'), newline)), Burrow::from_lines)(input)
+}
 
-    states.insert(input.to_owned(), 0_u32);
-    unvisited.insert(input.to_owned());
+#[derive(Clone, Debug)]
+struct Solution {
+    energy: u64,
+    /// Every move along with the burrow right after it
+    steps: Vec<(Move, Burrow)>,
+}
 
-    loop {
-        //println!("{} {}", states.len(), unvisited.len());
+/// A* search over the burrow states, `None` if the amphipods can't be organised
+fn solve(burrow: &Burrow) -> Option<Solution> {
+    let mut costs = HashMap::from([(burrow.clone(), 0)]);
+    let mut previous: HashMap<Burrow, (Burrow, Move)> = HashMap::new();
+    let mut queue = BinaryHeap::from([Reverse((burrow.lower_bound(), 0, burrow.clone()))]);
 
-        let (maps, cost) = all_min(&states, &unvisited);
+    while let Some(Reverse((_, cost, state))) = queue.pop() {
+        if cost > costs[&state] {
+            continue;
+        }
 
-        //println!("{}", cost);
+        if state.is_done() {
+            let mut steps = Vec::new();
+            let mut current = state;
 
-        for map in maps {
-            if is_win(&map) {
-                return cost;
+            while let Some((before, movement)) = previous.remove(&current) {
+                steps.push((movement, current));
+                current = before;
             }
 
-            for (new_state, move_cost) in moves(&map) {
-                let total_cost = cost + move_cost;
+            steps.reverse();
 
-                if let Some(current_cost) = states.get(&new_state) {
-                    if total_cost < *current_cost {
-                        states.insert(new_state, total_cost);
-                    }
-                } else {
-                    states.insert(new_state.clone(), total_cost);
-                    unvisited.insert(new_state);
-                }
-            }
+            return Some(Solution { energy: cost, steps });
+        }
+
+        for movement in state.moves() {
+            let next = state.apply(&movement);
+            let next_cost = cost + movement.energy;
 
-            unvisited.remove(&map);
+            if costs.get(&next).is_none_or(|&known| next_cost < known) {
+                costs.insert(next.clone(), next_cost);
+                previous.insert(next.clone(), (state.clone(), movement));
+                queue.push(Reverse((next_cost + next.lower_bound(), next_cost, next)));
+            }
         }
     }
+
+    None
 }
 
 fn main() {
     let input = read(23);
 
-    let part_1_map = parse(parser, &input);
-    let part_2_map = Map {
-        corridor: [None, None, None, None, None, None, None],
-        rooms: [
-            [part_1_map.rooms[0][0], Some(Type::Desert), Some(Type::Desert), part_1_map.rooms[0][1]],
-            [part_1_map.rooms[1][0], Some(Type::Copper), Some(Type::Bronze), part_1_map.rooms[1][1]],
-            [part_1_map.rooms[2][0], Some(Type::Bronze), Some(Type::Amber), part_1_map.rooms[2][1]],
-            [part_1_map.rooms[3][0], Some(Type::Amber), Some(Type::Copper), part_1_map.rooms[3][1]],
-        ]
-    };
-
-    println!("Part 1: {}", solve(&part_1_map));
-    println!("Part 2: {}", solve(&part_2_map));
-}
+    let burrow = parse(parser, &input);
 
-#[cfg(test)]
-mod tests {
-    use super::outsides;
-    use super::moves;
-    use super::move_cost;
-    use super::move_inside;
-    use super::move_outside;
-    use super::solve;
-    use super::is_win;
-    use super::parser;
-    use super::Type;
-    use super::Map;
+    if std::env::args().nth(1).as_deref() == Some("--print") {
+        for burrow in [Some(burrow.clone()), burrow.unfold()].into_iter().flatten() {
+            println!("{}\n", burrow);
 
-    #[test]
-    fn test_outsides() {
-        // #############
-        // #01.2.3.4.56#
-        // ###0#1#2#3###
-        //   #.#.#.#.#
-        //   #########
-        assert_eq!(outsides(0), [1, 2]);
-        assert_eq!(outsides(1), [2, 3]);
-        assert_eq!(outsides(2), [3, 4]);
-        assert_eq!(outsides(3), [4, 5]);
+            let Some(solution) = solve(&burrow) else {
+                println!("No solution\n");
+                continue;
+            };
+
+            for (movement, state) in &solution.steps {
+                println!("{}\n{}\n", movement, state);
+            }
+            println!("Total: {} energy\n", solution.energy);
+        }
+
+        return;
     }
 
-    #[test]
-    fn test_move_cost() {
-        // #############
-        // #01.2.3.4.56#
-        // ###0#1#2#3###
-        //   #.#.#.#.#
-        //   #########
-        assert_eq!(move_cost(0, 0, 1, 1), 2);
-        assert_eq!(move_cost(0, 1, 1, 1), 3);
-        assert_eq!(move_cost(0, 0, 0, 1), 3);
-        assert_eq!(move_cost(0, 1, 0, 1), 4);
-        assert_eq!(move_cost(1, 0, 1, 1), 4);
+    match solve(&burrow) {
+        Some(solution) => println!("Part 1: {}", solution.energy),
+        None => println!("Part 1: no solution"),
     }
 
-    #[test]
-    fn test_parser() {
-        let text = "#############
+    match burrow.unfold().map(|unfolded| solve(&unfolded)) {
+        Some(Some(solution)) => println!("Part 2: {}", solution.energy),
+        Some(None) => println!("Part 2: no solution"),
+        None => println!("Part 2: only defined for four rooms two deep"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#############
 #...........#
 ###B#C#B#D###
   #A#D#C#A#
   #########
 ";
 
-        assert_eq!(parser(text), Ok(("", Map {
-            corridor: [None, None, None, None, None, None, None],
-            rooms: [
-                [Some(Type::Bronze), Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Amber)],
-            ],
-        })));
-    }
-
     #[test]
-    fn test_move_inside_1() {
-        let map = Map {
-            corridor: [Some(Type::Bronze), None, None, None, None, None, None],
-            rooms: [
-                [None, Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Amber)],
+    fn test_parser() {
+        let burrow = parse(parser, INPUT);
+
+        assert_eq!(burrow, Burrow {
+            hallway: vec![None; 11],
+            doors: vec![2, 4, 6, 8],
+            rooms: vec![
+                vec![Some(1), Some(0)],
+                vec![Some(2), Some(3)],
+                vec![Some(1), Some(2)],
+                vec![Some(3), Some(0)],
             ],
-        };
-
-        let mut moves = Vec::new();
+        });
+        assert_eq!(format!("{}\n", burrow), INPUT);
 
-        move_inside(&mut moves, &map, 0, 0);
-
-        assert_eq!(moves, Vec::new());
+        assert!(parser("#####\n#...#\n###A###\n  #B#\n  ###\n").is_err());
     }
 
     #[test]
-    fn test_move_inside_2() {
-        let map = Map {
-            corridor: [Some(Type::Amber), None, None, None, None, None, None],
-            rooms: [
-                [None, Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Bronze)],
-            ],
-        };
-
-        let mut moves = Vec::new();
-
-        move_inside(&mut moves, &map, 0, 0);
-
-        assert_eq!(moves, vec![
-            (Map {
-                corridor: [None, None, None, None, None, None, None],
-                rooms: [
-                    [Some(Type::Amber), Some(Type::Amber)],
-                    [Some(Type::Copper), Some(Type::Desert)],
-                    [Some(Type::Bronze), Some(Type::Copper)],
-                    [Some(Type::Desert), Some(Type::Bronze)],
-                ],
-            }, 3),
-        ]);
+    fn test_unfold() {
+        assert_eq!(parse(parser, INPUT).unfold().unwrap().to_string(), "#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########");
     }
 
     #[test]
-    fn test_move_inside_3() {
-        let map = Map {
-            corridor: [None, None, None, None, None, None, Some(Type::Amber)],
-            rooms: [
-                [None, Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Bronze)],
-            ],
-        };
+    fn test_moves() {
+        let burrow = parse(parser, INPUT);
 
-        let mut moves = Vec::new();
+        assert_eq!(burrow.moves().len(), 4 * 7);
 
-        move_inside(&mut moves, &map, 0, 0);
-
-        assert_eq!(moves, vec![
-            (Map {
-                corridor: [None, None, None, None, None, None, None],
-                rooms: [
-                    [Some(Type::Amber), Some(Type::Amber)],
-                    [Some(Type::Copper), Some(Type::Desert)],
-                    [Some(Type::Bronze), Some(Type::Copper)],
-                    [Some(Type::Desert), Some(Type::Bronze)],
-                ],
-            }, 9),
-        ]);
+        let burrow = parse(parser, "#############
+#.....D.D.A.#
+###.#B#C#.###
+  #A#B#C#.#
+  #########
+");
+        let moves = burrow.moves();
+
+        assert_eq!(moves, vec![Move {
+            amphipod: 3,
+            from: Location::Hallway(7),
+            to: Location::Room(3, 1),
+            energy: 3000,
+        }]);
     }
 
     #[test]
-    fn test_move_outside_1() {
-        let map = Map {
-            corridor: [None, None, Some(Type::Copper), None, None, None, None],
-            rooms: [
-                [Some(Type::Bronze), Some(Type::Amber)],
-                [None, Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Amber)],
-            ],
-        };
-
-        let mut moves = Vec::new();
-
-        move_outside(&mut moves, &map, 0, 0);
-
-        assert_eq!(moves, vec![
-            (Map {
-                corridor: [None, Some(Type::Bronze), Some(Type::Copper), None, None, None, None],
-                rooms: [
-                    [None, Some(Type::Amber)],
-                    [None, Some(Type::Desert)],
-                    [Some(Type::Bronze), Some(Type::Copper)],
-                    [Some(Type::Desert), Some(Type::Amber)],
-                ],
-            }, 20),
-            (Map {
-                corridor: [Some(Type::Bronze), None, Some(Type::Copper), None, None, None, None],
-                rooms: [
-                    [None, Some(Type::Amber)],
-                    [None, Some(Type::Desert)],
-                    [Some(Type::Bronze), Some(Type::Copper)],
-                    [Some(Type::Desert), Some(Type::Amber)],
-                ],
-            }, 30),
-        ]);
+    fn test_solve_small() {
+        let burrow = parse(parser, "#########
+#.......#
+###B#A###
+  #A#B#
+  #####
+");
+        let solution = solve(&burrow).unwrap();
+
+        // One of them has to step aside, making it more than the lower bound
+        assert_eq!(burrow.lower_bound(), 44);
+        assert_eq!(solution.energy, 46);
+        assert_eq!(solution.steps.iter().map(|(movement, _)| movement.energy).sum::<u64>(), 46);
+        assert!(solution.steps.last().unwrap().1.is_done());
+        assert_eq!(burrow.unfold(), None);
     }
 
     #[test]
-    fn test_moves_1() {
-        let map = Map {
-            corridor: [None, None, None, None, None, None, None],
-            rooms: [
-                [Some(Type::Bronze), Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Amber)],
-            ],
-        };
+    fn test_too_many_rooms() {
+        // Rooms one deep, each holding its own amphipod
+        let rooms = |count: usize| {
+            let border = "#".repeat(2 * count + 3);
+            let cells: String = (0..count).map(|room| format!("{}#", name(Some(room as Amphipod)))).collect();
 
-        let moves = moves(&map);
+            format!("{}\n#{}#\n##{}#\n{}\n", border, ".".repeat(2 * count + 1), cells, border)
+        };
 
-        assert_eq!(moves.len(), 4 * 7);
+        assert!(parser(&rooms(MAX_ROOMS)).is_ok());
+        assert!(parser(&rooms(MAX_ROOMS + 1)).is_err());
     }
 
     #[test]
-    #[ignore]
     fn test_solve_part_1() {
-        let map = Map {
-            corridor: [None, None, None, None, None, None, None],
-            rooms: [
-                [Some(Type::Bronze), Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Amber)],
-            ],
-        };
+        let burrow = parse(parser, INPUT);
+        let solution = solve(&burrow).unwrap();
 
-        assert_eq!(solve(&map), 12521);
+        assert!(burrow.lower_bound() <= solution.energy);
+        assert_eq!(solution.energy, 12521);
     }
 
     #[test]
-    #[ignore]
     fn test_solve_part_2() {
-        let map = Map {
-            corridor: [None, None, None, None, None, None, None],
-            rooms: [
-                [Some(Type::Bronze), Some(Type::Desert), Some(Type::Desert), Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Copper), Some(Type::Bronze), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Bronze), Some(Type::Amber), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Amber), Some(Type::Copper), Some(Type::Amber)],
-            ],
-        };
-
-        assert_eq!(solve(&map), 44169);
-    }
-
-    #[test]
-    fn test_is_win_1() {
-        let map = Map {
-            corridor: [None, None, None, None, None, None, None],
-            rooms: [
-                [Some(Type::Bronze), Some(Type::Amber)],
-                [Some(Type::Copper), Some(Type::Desert)],
-                [Some(Type::Bronze), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Amber)],
-            ],
-        };
-
-        assert_eq!(is_win(&map), false);
-    }
-
-    #[test]
-    fn test_is_win_2() {
-        let map = Map {
-            corridor: [None, None, None, None, None, None, None],
-            rooms: [
-                [Some(Type::Amber), Some(Type::Amber)],
-                [Some(Type::Bronze), Some(Type::Bronze)],
-                [Some(Type::Copper), Some(Type::Copper)],
-                [Some(Type::Desert), Some(Type::Desert)],
-            ],
-        };
-
-        assert_eq!(is_win(&map), true);
+        assert_eq!(solve(&parse(parser, INPUT).unfold().unwrap()).unwrap().energy, 44169);
     }
 }
@@ -1,150 +1,435 @@
-use advent_of_code_2021::{parse, read};
+use advent_of_code_2021::{parse_lines, read};
+use nom::branch::alt;
 use nom::bytes::complete::tag;
+use nom::character::complete::char;
 use nom::character::complete::i64;
-use nom::multi::many0;
-use nom::sequence::tuple;
+use nom::combinator::map;
+use nom::combinator::value;
+use nom::sequence::preceded;
+use nom::sequence::separated_pair;
 use nom::IResult;
-use std::cmp::max;
-use std::cmp::min;
-
-fn bloc_parser(input: &str) -> IResult<&str, Bloc> {
-    let (input, _) = tag("inp w\n")(input)?;
-    let (input, _) = tag("mul x 0\n")(input)?;
-    let (input, _) = tag("add x z\n")(input)?;
-    let (input, _) = tag("mod x 26\n")(input)?;
-    let (input, (_, a, _)) = tuple((tag("div z "), i64, tag("\n")))(input)?;
-    let (input, (_, b, _)) = tuple((tag("add x "), i64, tag("\n")))(input)?;
-    let (input, _) = tag("eql x w\n")(input)?;
-    let (input, _) = tag("eql x 0\n")(input)?;
-    let (input, _) = tag("mul y 0\n")(input)?;
-    let (input, _) = tag("add y 25\n")(input)?;
-    let (input, _) = tag("mul y x\n")(input)?;
-    let (input, _) = tag("add y 1\n")(input)?;
-    let (input, _) = tag("mul z y\n")(input)?;
-    let (input, _) = tag("mul y 0\n")(input)?;
-    let (input, _) = tag("add y w\n")(input)?;
-    let (input, (_, c, _)) = tuple((tag("add y "), i64, tag("\n")))(input)?;
-    let (input, _) = tag("mul y x\n")(input)?;
-    let (input, _) = tag("add z y\n")(input)?;
-
-    Ok((input, Bloc { a, b, c }))
-}
-
-#[derive(Clone)]
-struct Bloc {
-    a: i64, // a == 1 || a == 16
-    b: i64, // if a == 1 b >= 10 else b < 0
-    c: i64, // 1 <= c <= 16
-}
-
-/*
-mul x 0     x = x * 0       x = 0
-add x z     x = x + z       x = z
-mod x 26    x = x mod 26    x = z mod 26
-div z a     z = z / a       z = z / a
-add x b     x = x + b       x = x + b
-eql x w     x = x == w      x = (x + b) == w
-eql x 0     x = x == 0      x = (x + b) != w
-mul y 0     y = y * 0       y1 = 0
-add y 25    y = y + 25      y1 = 25
-mul y x     y = y * x       y1 = 25 * x
-add y 1     y = y + 1       y1 = 25 * x + 1
-mul z y     z = z * y       z = z * y1
-mul y 0     y = y * 0       y2 = 0
-add y w     y = y + w       y2 = w
-add y c     y = y + c       y2 = w + c
-mul y x     y = y * x       y2 = (w + c) * x
-add z y     z = z + y       z = z + y2
-*/
-/*
-impl Bloc {
-    fn run(&self, z: i64, w: i64) -> i64 {
-        let x = z.rem_euclid(26);
-        let z = z / self.a;
-
-        if x + self.b != w {
-            return z * 26 + w + self.c;
-        } else {
-            return z;
+use std::collections::HashSet;
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    fn index(self) -> usize {
+        match self {
+            Register::W => 0,
+            Register::X => 1,
+            Register::Y => 2,
+            Register::Z => 3,
         }
     }
+}
 
-    fn run2(&self, z: i64, w: i64) -> i64 {
-        if self.a == 1 {
-            return z * 26 + w + self.c;
-        } else {
-            let x = z.rem_euclid(26);
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum Operand {
+    Register(Register),
+    Value(i64),
+}
 
-            if x + self.b != w {
-                return z + w + self.c;
-            } else {
-                return z / 26;
-            }
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+fn register_parser(input: &str) -> IResult<&str, Register> {
+    alt((
+        value(Register::W, char('w')),
+        value(Register::X, char('x')),
+        value(Register::Y, char('y')),
+        value(Register::Z, char('z')),
+    ))(input)
+}
+
+fn operand_parser(input: &str) -> IResult<&str, Operand> {
+    alt((
+        map(register_parser, Operand::Register),
+        map(i64, Operand::Value),
+    ))(input)
+}
+
+fn binary_parser<'a>(name: &'static str, instruction: fn(Register, Operand) -> Instruction) -> impl FnMut(&'a str) -> IResult<&'a str, Instruction> {
+    map(
+        preceded(tag(name), preceded(char(' '), separated_pair(register_parser, char(' '), operand_parser))),
+        move |(register, operand)| instruction(register, operand),
+    )
+}
+
+fn instruction_parser(input: &str) -> IResult<&str, Instruction> {
+    alt((
+        map(preceded(tag("inp "), register_parser), Instruction::Inp),
+        binary_parser("add", Instruction::Add),
+        binary_parser("mul", Instruction::Mul),
+        binary_parser("div", Instruction::Div),
+        binary_parser("mod", Instruction::Mod),
+        binary_parser("eql", Instruction::Eql),
+    ))(input)
+}
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq,Hash,Default)]
+struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    fn get(&self, register: Register) -> i64 {
+        self.registers[register.index()]
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Value(value) => value,
         }
     }
-}*/
 
-fn solve_part_1(instructions: &Vec<Bloc>) -> u64 {
-    let mut num = [-1; 14];
-    let mut stack: Vec<(usize, Bloc)> = Vec::new();
+    /// Runs the program reading from `inputs`, returns `None` if the program overflows, divides by
+    /// zero, takes the modulo of a negative number or by a non positive one, or runs out of inputs
+    fn run(&mut self, program: &[Instruction], inputs: &mut impl Iterator<Item = i64>) -> Option<()> {
+        for instruction in program {
+            let (register, result) = match *instruction {
+                Instruction::Inp(register) => (register, inputs.next()?),
+                Instruction::Add(register, operand) => (register, self.get(register).checked_add(self.value(operand))?),
+                Instruction::Mul(register, operand) => (register, self.get(register).checked_mul(self.value(operand))?),
+                Instruction::Div(register, operand) => (register, self.get(register).checked_div(self.value(operand))?),
+                Instruction::Mod(register, operand) => {
+                    let (a, b) = (self.get(register), self.value(operand));
+
+                    if a < 0 || b <= 0 {
+                        return None;
+                    }
 
-    for i in 0..14 {
-        let this = instructions[i].clone();
-        if instructions[i].a == 1 {
-            stack.push((i, this));
+                    (register, a % b)
+                },
+                Instruction::Eql(register, operand) => (register, (self.get(register) == self.value(operand)) as i64),
+            };
+
+            self.registers[register.index()] = result;
+        }
+
+        Some(())
+    }
+}
+
+/// Closed range of the values a register can hold
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+struct Interval {
+    min: i64,
+    max: i64,
+}
+
+impl Interval {
+    fn new(min: i64, max: i64) -> Self {
+        Self { min, max }
+    }
+
+    fn constant(value: i64) -> Self {
+        Self::new(value, value)
+    }
+
+    fn contains(&self, value: i64) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    fn from_values(values: impl IntoIterator<Item = i64>) -> Self {
+        values.into_iter().fold(Self::new(i64::MAX, i64::MIN), |interval, value| {
+            Self::new(interval.min.min(value), interval.max.max(value))
+        })
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.min.saturating_add(other.min), self.max.saturating_add(other.max))
+    }
+
+    fn mul(self, other: Self) -> Self {
+        Self::from_values([
+            self.min.saturating_mul(other.min),
+            self.min.saturating_mul(other.max),
+            self.max.saturating_mul(other.min),
+            self.max.saturating_mul(other.max),
+        ])
+    }
+
+    /// Truncated division, ignoring a zero divisor since the program would be invalid
+    fn div(self, other: Self) -> Option<Self> {
+        let divisors: Vec<i64> = [other.min, other.max, -1, 1].into_iter()
+            .filter(|&divisor| divisor != 0 && other.contains(divisor))
+            .collect();
+
+        if divisors.is_empty() {
+            return None;
+        }
+
+        Some(Self::from_values(divisors.iter().flat_map(|&divisor| [self.min / divisor, self.max / divisor])))
+    }
+
+    /// Remainder, ignoring negative dividends and non positive divisors since the program would be invalid
+    fn rem(self, other: Self) -> Option<Self> {
+        if self.max < 0 || other.max <= 0 {
+            return None;
+        }
+
+        let dividend = Self::new(self.min.max(0), self.max);
+
+        if dividend.max < other.min.max(1) {
+            Some(dividend)
         } else {
-            let (j, poped) = stack.pop().unwrap();
+            Some(Self::new(0, dividend.max.min(other.max - 1)))
+        }
+    }
 
-            // this.w - this.b == poped.w + poped.c
-            // this.w = poped.w + this.b + poped.c
-            // poped.w = this.w - this.b - poped.c
-            num[i] = min(9, 9 + this.b + poped.c);
-            num[j] = min(9, 9 - this.b - poped.c);
+    fn eql(self, other: Self) -> Self {
+        if self.min == self.max && self == other {
+            Self::constant(1)
+        } else if self.max < other.min || other.max < self.min {
+            Self::constant(0)
+        } else {
+            Self::new(0, 1)
         }
     }
+}
+
+/// Over-approximation of the registers after running the program with each input in its interval,
+/// `None` if the program can't run to completion for any input
+fn run_intervals(registers: [Interval; 4], program: &[Instruction], inputs: &[Interval]) -> Option<[Interval; 4]> {
+    let mut registers = registers;
+    let mut inputs = inputs.iter();
 
-    let mut result = 0;
+    for instruction in program {
+        let value = |operand| match operand {
+            Operand::Register(register) => registers[register.index()],
+            Operand::Value(value) => Interval::constant(value),
+        };
 
-    for i in 0..14 {
-        result += (num[13 - i] as u64) * 10_u64.pow(i as u32);
+        let (register, result) = match *instruction {
+            Instruction::Inp(register) => (register, *inputs.next()?),
+            Instruction::Add(register, operand) => (register, registers[register.index()].add(value(operand))),
+            Instruction::Mul(register, operand) => (register, registers[register.index()].mul(value(operand))),
+            Instruction::Div(register, operand) => (register, registers[register.index()].div(value(operand))?),
+            Instruction::Mod(register, operand) => (register, registers[register.index()].rem(value(operand))?),
+            Instruction::Eql(register, operand) => (register, registers[register.index()].eql(value(operand))),
+        };
+
+        registers[register.index()] = result;
     }
 
-    result
+    Some(registers)
 }
 
-fn solve_part_2(instructions: &Vec<Bloc>) -> u64 {
-    let mut num = [-1; 14];
-    let mut stack: Vec<(usize, Bloc)> = Vec::new();
+fn accepts(registers: Option<[Interval; 4]>) -> bool {
+    registers.is_some_and(|registers| registers[Register::Z.index()].contains(0))
+}
 
-    for i in 0..14 {
-        let this = instructions[i].clone();
-        if instructions[i].a == 1 {
-            stack.push((i, this));
-        } else {
-            let (j, poped) = stack.pop().unwrap();
+fn input_count(program: &[Instruction]) -> usize {
+    program.iter().filter(|instruction| matches!(instruction, Instruction::Inp(_))).count()
+}
+
+/// Digits that can appear at each position of an accepted model number, as far as the interval
+/// analysis can tell with the other digits left free
+fn digit_constraints(program: &[Instruction]) -> Vec<Vec<i64>> {
+    let count = input_count(program);
+    let initial = [Interval::constant(0); 4];
+
+    (0..count)
+        .map(|position| {
+            (1..=9)
+                .filter(|&digit| {
+                    let mut inputs = vec![Interval::new(1, 9); count];
+                    inputs[position] = Interval::constant(digit);
+
+                    accepts(run_intervals(initial, program, &inputs))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+struct Solver<'a> {
+    program: &'a [Instruction],
+    /// Start of the part of the program reading each digit
+    starts: Vec<usize>,
+    /// Candidate digits for each position, in the order they should be tried
+    digits: Vec<Vec<i64>>,
+    dead_ends: HashSet<(usize, Alu)>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(program: &'a [Instruction], largest: bool) -> Self {
+        let starts = program.iter()
+            .enumerate()
+            .filter(|(_, instruction)| matches!(instruction, Instruction::Inp(_)))
+            .map(|(index, _)| index)
+            .collect();
 
-            // this.w - this.b == poped.w + poped.c
-            // this.w = poped.w + this.b + poped.c
-            // poped.w = this.w - this.b - poped.c
-            num[i] = max(1, 1 + this.b + poped.c);
-            num[j] = max(1, 1 - this.b - poped.c);
+        let mut digits = digit_constraints(program);
+
+        if largest {
+            digits.iter_mut().for_each(|candidates| candidates.reverse());
         }
+
+        Self { program, starts, digits, dead_ends: HashSet::new() }
+    }
+
+    fn end(&self, position: usize) -> usize {
+        self.starts.get(position + 1).copied().unwrap_or(self.program.len())
+    }
+
+    fn feasible(&self, position: usize, alu: &Alu) -> bool {
+        let registers = alu.registers.map(Interval::constant);
+        let inputs: Vec<Interval> = self.digits[position..].iter().map(|candidates| Interval::from_values(candidates.iter().copied())).collect();
+
+        accepts(run_intervals(registers, &self.program[self.starts[position]..], &inputs))
     }
 
-    let mut result = 0;
+    fn search(&mut self, position: usize, alu: Alu) -> Option<Vec<i64>> {
+        if position == self.starts.len() {
+            return (alu.get(Register::Z) == 0).then(Vec::new);
+        }
+
+        if self.dead_ends.contains(&(position, alu)) || !self.feasible(position, &alu) {
+            return None;
+        }
+
+        for digit in self.digits[position].clone() {
+            let mut next = alu;
 
-    for i in 0..14 {
-        result += (num[13 - i] as u64) * 10_u64.pow(i as u32);
+            if next.run(&self.program[self.starts[position]..self.end(position)], &mut [digit].into_iter()).is_none() {
+                continue;
+            }
+
+            if let Some(mut rest) = self.search(position + 1, next) {
+                rest.insert(0, digit);
+
+                return Some(rest);
+            }
+        }
+
+        self.dead_ends.insert((position, alu));
+
+        None
     }
 
-    result
+    fn solve(&mut self) -> Option<u64> {
+        let mut alu = Alu::default();
+
+        alu.run(&self.program[..self.starts.first().copied().unwrap_or(self.program.len())], &mut std::iter::empty())?;
+
+        let digits = self.search(0, alu)?;
+
+        Some(digits.into_iter().fold(0, |number, digit| number * 10 + digit as u64))
+    }
+}
+
+fn solve_part_1(program: &[Instruction]) -> Option<u64> {
+    Solver::new(program, true).solve()
+}
+
+fn solve_part_2(program: &[Instruction]) -> Option<u64> {
+    Solver::new(program, false).solve()
 }
 
 fn main() {
     let input = read(24);
 
-    let parsed_input = parse(many0(bloc_parser), &input);
+    let parsed_input = parse_lines(instruction_parser, &input);
+
+    println!("Part 1: {}", solve_part_1(&parsed_input).expect("No model number accepted"));
+    println!("Part 2: {}", solve_part_2(&parsed_input).expect("No model number accepted"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(input: &str) -> Vec<Instruction> {
+        parse_lines(instruction_parser, input)
+    }
+
+    fn run(program: &[Instruction], inputs: &[i64]) -> Option<[i64; 4]> {
+        let mut alu = Alu::default();
+
+        alu.run(program, &mut inputs.iter().copied())?;
+
+        Some(alu.registers)
+    }
+
+    /// Same shape as the real MONAD programs, one block per digit
+    fn monad(blocks: &[(i64, i64, i64)]) -> String {
+        blocks.iter()
+            .map(|(a, b, c)| format!(
+                "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\nmul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\nmul y x\nadd z y\n",
+                a, b, c,
+            ))
+            .collect()
+    }
 
-    println!("Part 1: {}", solve_part_1(&parsed_input));
-    println!("Part 2: {}", solve_part_2(&parsed_input));
+    #[test]
+    fn test_instruction_parser() {
+        assert_eq!(instruction_parser("inp w"), Ok(("", Instruction::Inp(Register::W))));
+        assert_eq!(instruction_parser("mul x -1"), Ok(("", Instruction::Mul(Register::X, Operand::Value(-1)))));
+        assert_eq!(instruction_parser("eql z x"), Ok(("", Instruction::Eql(Register::Z, Operand::Register(Register::X)))));
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!(run(&program("inp x\nmul x -1\n"), &[5]), Some([0, -5, 0, 0]));
+        assert_eq!(run(&program("inp z\ninp x\nmul z 3\neql z x\n"), &[3, 9]), Some([0, 9, 0, 1]));
+        assert_eq!(run(&program("inp z\ninp x\nmul z 3\neql z x\n"), &[3, 8]), Some([0, 8, 0, 0]));
+        assert_eq!(
+            run(&program("inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2\n"), &[13]),
+            Some([1, 1, 0, 1]),
+        );
+        assert_eq!(run(&program("inp w\ndiv w 0\n"), &[1]), None);
+        assert_eq!(run(&program("inp w\nmod w 2\n"), &[-1]), None);
+        assert_eq!(run(&program("inp w\ninp x\n"), &[1]), None);
+        assert_eq!(run(&program("inp w\nmul w w\nmul w w\n"), &[1 << 20]), None);
+        assert_eq!(run(&program(&format!("inp w\nadd w {}\n", i64::MAX)), &[1]), None);
+    }
+
+    #[test]
+    fn test_digit_constraints() {
+        let program = program("inp w\nadd z w\nadd z 3\ninp x\neql z x\neql z 0\n");
+
+        assert_eq!(digit_constraints(&program), vec![vec![1, 2, 3, 4, 5, 6], vec![4, 5, 6, 7, 8, 9]]);
+    }
+
+    #[test]
+    fn test_solve() {
+        let program = program("inp w\nadd z w\nadd z 3\ninp x\neql z x\neql z 0\n");
+
+        assert_eq!(solve_part_1(&program), Some(69));
+        assert_eq!(solve_part_2(&program), Some(14));
+        assert_eq!(solve_part_1(&self::program("inp w\nadd z 1\n")), None);
+    }
+
+    #[test]
+    fn test_solve_monad() {
+        let program = program(&monad(&[(1, 12, 4), (1, 11, 10), (26, -13, 5), (1, 14, 3), (26, -2, 7), (26, -8, 6)]));
+
+        let accepted: Vec<u64> = (0..9_u64.pow(6))
+            .map(|index| (0..6).fold((index, 0), |(index, number), _| (index / 9, number * 10 + index % 9 + 1)).1)
+            .filter(|number| {
+                let digits: Vec<i64> = number.to_string().bytes().map(|digit| (digit - b'0') as i64).collect();
+
+                run(&program, &digits).is_some_and(|registers| registers[3] == 0)
+            })
+            .collect();
+
+        assert_eq!(solve_part_1(&program), accepted.iter().max().copied());
+        assert_eq!(solve_part_2(&program), accepted.iter().min().copied());
+        assert!(!accepted.is_empty());
+    }
 }
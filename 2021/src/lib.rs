@@ -6,6 +6,12 @@ use nom::sequence::terminated;
 use nom::{Finish, Parser};
 use std::fs::read_to_string;
 
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/graph.rs"]
+pub mod graph;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/ocr.rs"]
+pub mod ocr;
 pub mod util;
 
 pub fn read(day: u8) -> String {
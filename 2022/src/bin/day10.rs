@@ -1,4 +1,6 @@
-use advent_of_code_2022::{read, parse};
+use std::fmt;
+
+use advent_of_code_2022::{ocr, read, parse};
 use itertools::Itertools;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
@@ -26,78 +28,105 @@ fn parser(input: &str) -> IResult<&str, Vec<Instruction>> {
     )(input)
 }
 
-fn solve_part1(input: &[Instruction]) -> i32 {
-    let mut registry = 1;
-    let mut values = Vec::from([1]);
-
-    for instruction in input {
-        match instruction {
-            Instruction::Noop => {
-                values.push(registry);
-            },
-            Instruction::Addx(amount) => {
-                values.push(registry);
-                values.push(registry);
-
-                registry += amount;
-            },
-        }
-    }
-
-    20 * values[20] + 60 * values[60] + 100 * values[100] + 140 * values[140] + 180 * values[180] + 220 * values[220]
-}
-
-struct Screen {
+/// Runs the instructions, yielding the cycle number and the value of the register during each cycle
+struct Cpu<'a> {
+    instructions: std::slice::Iter<'a, Instruction>,
     registry: i32,
-    screen: [[bool; 40]; 6],
     cycle: usize,
+    pending: Option<i32>,
 }
 
-impl Screen {
-    pub fn new() -> Self {
-        Screen {
+impl<'a> Cpu<'a> {
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        Cpu {
+            instructions: instructions.iter(),
             registry: 1,
-            screen: [[false; 40]; 6],
             cycle: 0,
+            pending: None,
         }
     }
+}
+
+impl Iterator for Cpu<'_> {
+    type Item = (usize, i32);
 
-    pub fn draw(&mut self) {
-        let cycle_vertical = self.cycle.div_euclid(40);
-        let cycle_horizontal = self.cycle.rem_euclid(40);
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(amount) = self.pending.take() {
+            self.cycle += 1;
+
+            let state = (self.cycle, self.registry);
+            self.registry += amount;
+
+            return Some(state);
+        }
 
-        if (cycle_horizontal as i32).abs_diff(self.registry) <= 1 {
-            self.screen[cycle_vertical][cycle_horizontal] = true;
+        if let Instruction::Addx(amount) = self.instructions.next()? {
+            self.pending = Some(*amount);
         }
 
         self.cycle += 1;
+
+        Some((self.cycle, self.registry))
     }
 }
 
-impl ToString for Screen {
-    fn to_string(&self) -> String {
-        self.screen.iter().map(|row| row.iter().map(|v| if *v { '#' } else { '.' }).collect::<String>()).join("\n")
-    }
+fn solve_part1(input: &[Instruction]) -> i32 {
+    Cpu::new(input)
+        .filter(|(cycle, _)| cycle % 40 == 20)
+        .take(6)
+        .map(|(cycle, registry)| cycle as i32 * registry)
+        .sum()
 }
 
-fn solve_part2(input: &[Instruction]) -> String {
-    let mut screen = Screen::new();
-
-    for instruction in input {
-        match instruction {
-            Instruction::Noop => {
-                screen.draw();
-            },
-            Instruction::Addx(amount) => {
-                screen.draw();
-                screen.draw();
-
-                screen.registry += amount;
-            },
+struct Crt {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Crt {
+    pub fn new(width: usize, height: usize) -> Self {
+        Crt {
+            width,
+            height,
+            pixels: vec![false; width * height],
         }
     }
 
-    screen.to_string()
+    /// Draws one pixel per cycle, lit when the sprite centered on the register covers it
+    pub fn draw(&mut self, states: impl IntoIterator<Item = (usize, i32)>) {
+        for (cycle, registry) in states.into_iter().take(self.width * self.height) {
+            let position = cycle - 1;
+
+            if ((position % self.width) as i32).abs_diff(registry) <= 1 {
+                self.pixels[position] = true;
+            }
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[bool]> {
+        self.pixels.chunks(self.width)
+    }
+}
+
+impl fmt::Display for Crt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let screen = self.rows().map(|row| row.iter().map(|v| if *v { '#' } else { '.' }).collect::<String>()).join("\n");
+
+        write!(f, "{}", screen)
+    }
+}
+
+fn screen(input: &[Instruction]) -> Crt {
+    let mut crt = Crt::new(40, 6);
+
+    crt.draw(Cpu::new(input));
+
+    crt
+}
+
+fn solve_part2(input: &[Instruction]) -> String {
+    screen(input).to_string()
 }
 
 fn main() {
@@ -106,7 +135,13 @@ fn main() {
     let parsed = parse(parser, &input);
 
     println!("{}", solve_part1(&parsed));
-    println!("{}", solve_part2(&parsed));
+
+    let screen = screen(&parsed);
+
+    match ocr::recognize(&screen.rows().collect::<Vec<_>>()) {
+        Ok(text) => println!("{}", text),
+        Err(error) => println!("{}\n{}", screen, error),
+    }
 }
 
 #[cfg(test)]
@@ -122,6 +157,23 @@ mod tests {
         ])));
     }
 
+    #[test]
+    fn test_cpu() {
+        let instructions = [Instruction::Noop, Instruction::Addx(3), Instruction::Addx(-5)];
+
+        assert_eq!(Cpu::new(&instructions).collect::<Vec<_>>(), vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+    }
+
+    #[test]
+    fn test_crt() {
+        let instructions = [Instruction::Addx(2), Instruction::Noop, Instruction::Noop, Instruction::Noop];
+        let mut crt = Crt::new(3, 2);
+
+        crt.draw(Cpu::new(&instructions));
+
+        assert_eq!(crt.to_string(), "###\n...");
+    }
+
     #[test]
     fn test_solve_part1() {
         assert_eq!(solve_part1(&vec![
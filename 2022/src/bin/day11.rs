@@ -1,14 +1,16 @@
+use advent_of_code_2022::number_theory::lcm_all;
 use advent_of_code_2022::{read, parse};
 use itertools::Itertools;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{u8, u64};
+use nom::character::complete::{one_of, space0, u8, u64};
 use nom::combinator::{map, value};
 use nom::IResult;
-use nom::multi::separated_list1;
-use nom::sequence::tuple;
-use std::cell::RefCell;
+use nom::multi::{fold_many0, separated_list1};
+use nom::sequence::{delimited, pair, tuple};
 use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
 
 #[derive(Clone, Debug, PartialEq)]
 struct Monkey {
@@ -19,36 +21,246 @@ struct Monkey {
     test_on_false: usize,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Operation {
-    operator: Operator,
-    operand: Operand,
+impl Monkey {
+    fn target(&self, worry: u64) -> usize {
+        if worry.is_multiple_of(self.test_divisible_by) { self.test_on_true } else { self.test_on_false }
+    }
+}
+
+/// Arithmetic expression computing the new worry level from the `old` one
+#[derive(Clone, Debug, PartialEq)]
+enum Operation {
+    Old,
+    Constant(u64),
+    Add(Box<Operation>, Box<Operation>),
+    Sub(Box<Operation>, Box<Operation>),
+    Mul(Box<Operation>, Box<Operation>),
+    Div(Box<Operation>, Box<Operation>),
 }
 
 impl Operation {
-    pub fn apply(&self, left: u64) -> u64 {
-        let right = match self.operand {
-            Operand::Old => left,
-            Operand::Fixed(value) => value,
-        };
+    fn apply(&self, old: u64) -> Result<u64, TroopError> {
+        match self {
+            Operation::Old => Ok(old),
+            Operation::Constant(value) => Ok(*value),
+            Operation::Add(left, right) => left.apply(old)?.checked_add(right.apply(old)?).ok_or(TroopError::Overflow),
+            Operation::Sub(left, right) => left.apply(old)?.checked_sub(right.apply(old)?).ok_or(TroopError::Underflow),
+            Operation::Mul(left, right) => left.apply(old)?.checked_mul(right.apply(old)?).ok_or(TroopError::Overflow),
+            Operation::Div(left, right) => left.apply(old)?.checked_div(right.apply(old)?).ok_or(TroopError::DivisionByZero),
+        }
+    }
+
+    /// Whether the result modulo `n` only depends on `old` modulo `n`, which is what makes
+    /// reducing worry levels modulo a common multiple safe
+    fn is_modular(&self) -> bool {
+        match self {
+            Operation::Old | Operation::Constant(_) => true,
+            Operation::Add(left, right) | Operation::Mul(left, right) => left.is_modular() && right.is_modular(),
+            Operation::Sub(_, _) | Operation::Div(_, _) => false,
+        }
+    }
+
+    fn parser(input: &str) -> IResult<&str, Self> {
+        Self::binary(Self::term, "+-")(input)
+    }
+
+    fn term(input: &str) -> IResult<&str, Self> {
+        Self::binary(Self::factor, "*/")(input)
+    }
+
+    fn factor(input: &str) -> IResult<&str, Self> {
+        delimited(
+            space0,
+            alt((
+                value(Operation::Old, tag("old")),
+                map(u64, Operation::Constant),
+                delimited(tag("("), Self::parser, tag(")")),
+            )),
+            space0,
+        )(input)
+    }
 
-        match self.operator {
-            Operator::Add => left + right,
-            Operator::Times => left * right,
+    /// Left associative chain of `operand`s joined by any of the `operators`
+    fn binary<'a>(
+        operand: fn(&'a str) -> IResult<&'a str, Self>,
+        operators: &'static str,
+    ) -> impl FnMut(&'a str) -> IResult<&'a str, Self> {
+        move |input| {
+            let (input, first) = operand(input)?;
+
+            fold_many0(
+                pair(one_of(operators), operand),
+                move || first.clone(),
+                |left, (operator, right)| {
+                    let (left, right) = (Box::new(left), Box::new(right));
+
+                    match operator {
+                        '+' => Operation::Add(left, right),
+                        '-' => Operation::Sub(left, right),
+                        '*' => Operation::Mul(left, right),
+                        _ => Operation::Div(left, right),
+                    }
+                },
+            )(input)
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Operator {
-    Add,
-    Times,
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Old => write!(f, "old"),
+            Operation::Constant(value) => write!(f, "{}", value),
+            Operation::Add(left, right) => write!(f, "({} + {})", left, right),
+            Operation::Sub(left, right) => write!(f, "({} - {})", left, right),
+            Operation::Mul(left, right) => write!(f, "({} * {})", left, right),
+            Operation::Div(left, right) => write!(f, "({} / {})", left, right),
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Operand {
-    Old,
-    Fixed(u64),
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TroopError {
+    Overflow,
+    Underflow,
+    DivisionByZero,
+    /// The operation of this monkey subtracts or divides, preventing worry levels from being
+    /// reduced modulo the tests
+    NotModular(usize),
+    /// The test of this monkey is a division by zero
+    ZeroTest(usize),
+    /// The monkey throws to a monkey that isn't in the troop
+    UnknownTarget(usize, usize),
+}
+
+impl fmt::Display for TroopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TroopError::Overflow => write!(f, "worry level overflow"),
+            TroopError::Underflow => write!(f, "negative worry level"),
+            TroopError::DivisionByZero => write!(f, "worry level divided by zero"),
+            TroopError::NotModular(monkey) => write!(f, "operation of monkey {} can't be computed modulo its tests", monkey),
+            TroopError::ZeroTest(monkey) => write!(f, "monkey {} tests divisibility by zero", monkey),
+            TroopError::UnknownTarget(monkey, target) => write!(f, "monkey {} throws to unknown monkey {}", monkey, target),
+        }
+    }
+}
+
+/// How worry levels are kept in check after each inspection
+trait WorryPolicy {
+    fn relieve(&self, worry: u64) -> u64;
+}
+
+/// Relief divides the worry level, as in part 1
+struct Divide(u64);
+
+impl Divide {
+    fn new(divisor: u64) -> Result<Self, TroopError> {
+        match divisor {
+            0 => Err(TroopError::DivisionByZero),
+            _ => Ok(Self(divisor)),
+        }
+    }
+}
+
+impl WorryPolicy for Divide {
+    fn relieve(&self, worry: u64) -> u64 {
+        worry / self.0
+    }
+}
+
+/// Worry levels are only kept modulo the least common multiple of every test, which doesn't
+/// change where items are thrown as long as every operation is modular
+struct ModuloLcm(u64);
+
+impl ModuloLcm {
+    fn new(monkeys: &[Monkey]) -> Result<Self, TroopError> {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if !monkey.operation.is_modular() {
+                return Err(TroopError::NotModular(i));
+            }
+            if monkey.test_divisible_by == 0 {
+                return Err(TroopError::ZeroTest(i));
+            }
+        }
+
+        Ok(Self(lcm_all(monkeys.iter().map(|monkey| monkey.test_divisible_by))))
+    }
+}
+
+impl WorryPolicy for ModuloLcm {
+    fn relieve(&self, worry: u64) -> u64 {
+        worry % self.0
+    }
+}
+
+impl<F> WorryPolicy for F where F: Fn(u64) -> u64 {
+    fn relieve(&self, worry: u64) -> u64 {
+        self(worry)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct MonkeyTroop {
+    monkeys: Vec<Monkey>,
+    inspections: Vec<u64>,
+}
+
+impl MonkeyTroop {
+    /// Troop of the monkeys, which must only throw to each other
+    fn new(monkeys: &[Monkey]) -> Result<Self, TroopError> {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if let Some(&target) = [monkey.test_on_true, monkey.test_on_false].iter().find(|&&target| target >= monkeys.len()) {
+                return Err(TroopError::UnknownTarget(i, target));
+            }
+        }
+
+        Ok(Self {
+            monkeys: monkeys.to_vec(),
+            inspections: vec![0; monkeys.len()],
+        })
+    }
+
+    fn items(&self, monkey: usize) -> &VecDeque<u64> {
+        &self.monkeys[monkey].items
+    }
+
+    /// Number of items inspected by each monkey so far
+    fn inspections(&self) -> &[u64] {
+        &self.inspections
+    }
+
+    fn round(&mut self, policy: &impl WorryPolicy) -> Result<(), TroopError> {
+        for i in 0..self.monkeys.len() {
+            let items = mem::take(&mut self.monkeys[i].items);
+
+            self.inspections[i] += items.len() as u64;
+
+            for item in items {
+                let worry = policy.relieve(self.monkeys[i].operation.apply(item)?);
+                let target = self.monkeys[i].target(worry);
+
+                self.monkeys[target].items.push_back(worry);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Plays `rounds` rounds, calling `on_round` with the number of the round just played
+    fn run(&mut self, rounds: usize, policy: &impl WorryPolicy, mut on_round: impl FnMut(usize, &Self)) -> Result<(), TroopError> {
+        for round in 1..=rounds {
+            self.round(policy)?;
+            on_round(round, self);
+        }
+
+        Ok(())
+    }
+
+    /// Product of the inspections of the two most active monkeys
+    fn monkey_business(&self) -> u64 {
+        self.inspections.iter().sorted().rev().take(2).product()
+    }
 }
 
 fn parser(input: &str) -> IResult<&str, Vec<Monkey>> {
@@ -59,16 +271,8 @@ fn parser(input: &str) -> IResult<&str, Vec<Monkey>> {
             u8,
             tag(":\n  Starting items: "),
             separated_list1(tag(", "), u64),
-            tag("\n  Operation: new = old "),
-            alt((
-                value(Operator::Add, tag("+")),
-                value(Operator::Times, tag("*")),
-            )),
-            tag(" "),
-            alt((
-                map(tag("old"), |_| Operand::Old),
-                map(u64, |v| Operand::Fixed(v)),
-            )),
+            tag("\n  Operation: new ="),
+            Operation::parser,
             tag("\n  Test: divisible by "),
             u64,
             tag("\n    If true: throw to monkey "),
@@ -76,12 +280,9 @@ fn parser(input: &str) -> IResult<&str, Vec<Monkey>> {
             tag("\n    If false: throw to monkey "),
             map(u8, |v| v as usize),
             tag("\n")
-        )), |(_, _, _, items, _, operator, _, operand, _, test_divisible_by, _, test_on_true, _, test_on_false, _)| Monkey {
+        )), |(_, _, _, items, _, operation, _, test_divisible_by, _, test_on_true, _, test_on_false, _)| Monkey {
             items: VecDeque::from(items),
-            operation: Operation {
-                operator,
-                operand,
-            },
+            operation,
             test_divisible_by,
             test_on_true,
             test_on_false,
@@ -89,76 +290,71 @@ fn parser(input: &str) -> IResult<&str, Vec<Monkey>> {
     )(input)
 }
 
-fn solve_part1(input: &[Monkey]) -> u32 {
-    let mut throws: Vec<u32> = input.iter().map(|_| 0).collect();
-    let monkeys: Vec<RefCell<Monkey>> = input.iter().map(|monkey| RefCell::new(monkey.clone())).collect();
-
-    for _ in 0..20 {
-        for (i, monkey) in monkeys.iter().enumerate() {
-            let operation = monkey.borrow().operation;
-            let test_divisible_by = monkey.borrow().test_divisible_by;
-            let test_on_true = monkey.borrow().test_on_true;
-            let test_on_false = monkey.borrow().test_on_false;
+fn simulate(input: &[Monkey], rounds: usize, policy: &impl WorryPolicy) -> Result<u64, TroopError> {
+    let mut troop = MonkeyTroop::new(input)?;
 
-            while let Some(item) = monkey.borrow_mut().items.pop_front() {
-                let new_wory = operation.apply(item);
-                let new_wory = new_wory / 3;
-                let test = new_wory.rem_euclid(test_divisible_by) == 0;
-
-                let monkey_to_throw = if test { test_on_true } else { test_on_false };
-
-                monkeys[monkey_to_throw].borrow_mut().items.push_back(new_wory);
-
-                throws[i] += 1;
-            }
-        }
-    }
+    troop.run(rounds, policy, |_, _| {})?;
 
-    throws.iter().sorted().rev().take(2).product()
+    Ok(troop.monkey_business())
 }
 
-fn solve_part2(input: &[Monkey]) -> u64 {
-    let mut throws: Vec<u64> = input.iter().map(|_| 0).collect();
-    let monkeys: Vec<RefCell<Monkey>> = input.iter().map(|monkey| RefCell::new(monkey.clone())).collect();
-    let remnant: u64 = input.iter().map(|monkey| monkey.test_divisible_by).product();
+fn solve_part1(input: &[Monkey]) -> Result<u64, TroopError> {
+    simulate(input, 20, &Divide::new(3)?)
+}
 
-    for _ in 0..10_000 {
-        for (i, monkey) in monkeys.iter().enumerate() {
-            let operation = monkey.borrow().operation;
-            let test_divisible_by = monkey.borrow().test_divisible_by;
-            let test_on_true = monkey.borrow().test_on_true;
-            let test_on_false = monkey.borrow().test_on_false;
+fn solve_part2(input: &[Monkey]) -> Result<u64, TroopError> {
+    simulate(input, 10_000, &ModuloLcm::new(input)?)
+}
 
-            while let Some(item) = monkey.borrow_mut().items.pop_front() {
-                let new_wory = operation.apply(item);
-                let new_wory = new_wory % remnant;
-                let test = new_wory.rem_euclid(test_divisible_by) == 0;
+fn main() {
+    let input = read(11);
 
-                let monkey_to_throw = if test { test_on_true } else { test_on_false };
+    let parsed = parse(parser, &input);
 
-                monkeys[monkey_to_throw].borrow_mut().items.push_back(new_wory);
+    if std::env::args().nth(1).as_deref() == Some("--trace") {
+        let mut troop = match MonkeyTroop::new(&parsed) {
+            Ok(troop) => troop,
+            Err(error) => {
+                println!("Invalid troop: {}", error);
+                return;
+            },
+        };
 
-                throws[i] += 1;
+        let result = troop.run(20, &Divide(3), |round, troop| {
+            println!("After round {}:", round);
+            for (i, monkey) in troop.monkeys.iter().enumerate() {
+                println!("Monkey {} ({}): {}", i, monkey.operation, troop.items(i).iter().join(", "));
             }
-        }
-    }
-
-    throws.iter().sorted().rev().take(2).product()
-}
+        });
+        println!("Inspections: {}", troop.inspections().iter().join(", "));
 
-fn main() {
-    let input = read(11);
+        if let Err(error) = result {
+            println!("Stopped: {}", error);
+        }
 
-    let parsed = parse(parser, &input);
+        return;
+    }
 
-    println!("{}", solve_part1(&parsed));
-    println!("{}", solve_part2(&parsed));
+    for solution in [solve_part1(&parsed), solve_part2(&parsed)] {
+        match solution {
+            Ok(monkey_business) => println!("{}", monkey_business),
+            Err(error) => println!("Invalid troop: {}", error),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn add(left: Operation, right: Operation) -> Operation {
+        Operation::Add(Box::new(left), Box::new(right))
+    }
+
+    fn mul(left: Operation, right: Operation) -> Operation {
+        Operation::Mul(Box::new(left), Box::new(right))
+    }
+
     const INPUT: &str = "Monkey 0:
   Starting items: 79, 98
   Operation: new = old * 19
@@ -193,40 +389,28 @@ Monkey 3:
         assert_eq!(parser(INPUT), Ok(("", vec![
             Monkey {
                 items: VecDeque::from(vec![79, 98]),
-                operation: Operation {
-                    operator: Operator::Times,
-                    operand: Operand::Fixed(19),
-                },
+                operation: mul(Operation::Old, Operation::Constant(19)),
                 test_divisible_by: 23,
                 test_on_true: 2,
                 test_on_false: 3,
             },
             Monkey {
                 items: VecDeque::from(vec![54, 65, 75, 74]),
-                operation: Operation {
-                    operator: Operator::Add,
-                    operand: Operand::Fixed(6),
-                },
+                operation: add(Operation::Old, Operation::Constant(6)),
                 test_divisible_by: 19,
                 test_on_true: 2,
                 test_on_false: 0,
             },
             Monkey {
                 items: VecDeque::from(vec![79, 60, 97]),
-                operation: Operation {
-                    operator: Operator::Times,
-                    operand: Operand::Old,
-                },
+                operation: mul(Operation::Old, Operation::Old),
                 test_divisible_by: 13,
                 test_on_true: 1,
                 test_on_false: 3,
             },
             Monkey {
                 items: VecDeque::from(vec![74]),
-                operation: Operation {
-                    operator: Operator::Add,
-                    operand: Operand::Fixed(3),
-                },
+                operation: add(Operation::Old, Operation::Constant(3)),
                 test_divisible_by: 17,
                 test_on_true: 0,
                 test_on_false: 1,
@@ -239,45 +423,33 @@ Monkey 3:
         assert_eq!(solve_part1(&vec![
             Monkey {
                 items: VecDeque::from(vec![79, 98]),
-                operation: Operation {
-                    operator: Operator::Times,
-                    operand: Operand::Fixed(19),
-                },
+                operation: mul(Operation::Old, Operation::Constant(19)),
                 test_divisible_by: 23,
                 test_on_true: 2,
                 test_on_false: 3,
             },
             Monkey {
                 items: VecDeque::from(vec![54, 65, 75, 74]),
-                operation: Operation {
-                    operator: Operator::Add,
-                    operand: Operand::Fixed(6),
-                },
+                operation: add(Operation::Old, Operation::Constant(6)),
                 test_divisible_by: 19,
                 test_on_true: 2,
                 test_on_false: 0,
             },
             Monkey {
                 items: VecDeque::from(vec![79, 60, 97]),
-                operation: Operation {
-                    operator: Operator::Times,
-                    operand: Operand::Old,
-                },
+                operation: mul(Operation::Old, Operation::Old),
                 test_divisible_by: 13,
                 test_on_true: 1,
                 test_on_false: 3,
             },
             Monkey {
                 items: VecDeque::from(vec![74]),
-                operation: Operation {
-                    operator: Operator::Add,
-                    operand: Operand::Fixed(3),
-                },
+                operation: add(Operation::Old, Operation::Constant(3)),
                 test_divisible_by: 17,
                 test_on_true: 0,
                 test_on_false: 1,
             },
-        ]), 10605);
+        ]), Ok(10605));
     }
 
     #[test]
@@ -285,44 +457,85 @@ Monkey 3:
         assert_eq!(solve_part2(&vec![
             Monkey {
                 items: VecDeque::from(vec![79, 98]),
-                operation: Operation {
-                    operator: Operator::Times,
-                    operand: Operand::Fixed(19),
-                },
+                operation: mul(Operation::Old, Operation::Constant(19)),
                 test_divisible_by: 23,
                 test_on_true: 2,
                 test_on_false: 3,
             },
             Monkey {
                 items: VecDeque::from(vec![54, 65, 75, 74]),
-                operation: Operation {
-                    operator: Operator::Add,
-                    operand: Operand::Fixed(6),
-                },
+                operation: add(Operation::Old, Operation::Constant(6)),
                 test_divisible_by: 19,
                 test_on_true: 2,
                 test_on_false: 0,
             },
             Monkey {
                 items: VecDeque::from(vec![79, 60, 97]),
-                operation: Operation {
-                    operator: Operator::Times,
-                    operand: Operand::Old,
-                },
+                operation: mul(Operation::Old, Operation::Old),
                 test_divisible_by: 13,
                 test_on_true: 1,
                 test_on_false: 3,
             },
             Monkey {
                 items: VecDeque::from(vec![74]),
-                operation: Operation {
-                    operator: Operator::Add,
-                    operand: Operand::Fixed(3),
-                },
+                operation: add(Operation::Old, Operation::Constant(3)),
                 test_divisible_by: 17,
                 test_on_true: 0,
                 test_on_false: 1,
             },
-        ]), 2713310158);
+        ]), Ok(2713310158));
+    }
+
+    #[test]
+    fn test_operation() {
+        let (rest, operation) = Operation::parser(" old * (old + 2) - 3 / old").unwrap();
+
+        assert_eq!(rest, "");
+        assert_eq!(operation.to_string(), "((old * (old + 2)) - (3 / old))");
+        assert_eq!(operation.apply(3), Ok(14));
+        assert!(!operation.is_modular());
+        assert_eq!(Operation::parser(" old - 10").unwrap().1.apply(3), Err(TroopError::Underflow));
+        assert_eq!(Operation::parser(" old / 0").unwrap().1.apply(3), Err(TroopError::DivisionByZero));
+        assert_eq!(Operation::parser(" old * old").unwrap().1.apply(u64::MAX), Err(TroopError::Overflow));
+        assert!(mul(Operation::Old, add(Operation::Old, Operation::Constant(1))).is_modular());
+    }
+
+    #[test]
+    fn test_troop() {
+        let monkeys = parse(parser, INPUT);
+        let mut troop = MonkeyTroop::new(&monkeys).unwrap();
+        let mut snapshots = Vec::new();
+
+        troop.run(20, &Divide(3), |round, troop| {
+            if round == 1 {
+                snapshots = (0..4).map(|i| troop.items(i).iter().copied().collect::<Vec<_>>()).collect();
+            }
+        }).unwrap();
+
+        assert_eq!(snapshots, vec![vec![20, 23, 27, 26], vec![2080, 25, 167, 207, 401, 1046], vec![], vec![]]);
+        assert_eq!(troop.inspections(), &[101, 95, 7, 105]);
+
+        let mut troop = MonkeyTroop::new(&monkeys).unwrap();
+        troop.run(20, &|worry: u64| worry % 96577, |_, _| {}).unwrap();
+
+        assert_eq!(troop.inspections(), &[99, 97, 8, 103]);
+    }
+
+    #[test]
+    fn test_troop_errors() {
+        let mut monkeys = parse(parser, INPUT);
+
+        monkeys[2].test_divisible_by = 0;
+        assert_eq!(solve_part2(&monkeys), Err(TroopError::ZeroTest(2)));
+
+        monkeys[1].operation = Operation::parser(" old - 100").unwrap().1;
+        assert_eq!(solve_part2(&monkeys), Err(TroopError::NotModular(1)));
+        assert_eq!(solve_part1(&monkeys), Err(TroopError::Underflow));
+
+        monkeys[3].test_on_false = 4;
+        assert_eq!(MonkeyTroop::new(&monkeys).err(), Some(TroopError::UnknownTarget(3, 4)));
+        assert_eq!(solve_part1(&monkeys), Err(TroopError::UnknownTarget(3, 4)));
+
+        assert!(matches!(Divide::new(0), Err(TroopError::DivisionByZero)));
     }
 }
@@ -1,96 +1,37 @@
+use advent_of_code_2022::nested_list::{divider_indices, NestedList};
 use advent_of_code_2022::{read, parse};
-use itertools::Itertools;
-use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::u8;
-use nom::combinator::map;
 use nom::IResult;
 use nom::multi::separated_list0;
-use nom::sequence::{delimited, pair, terminated};
-use std::cmp::{max, Ordering};
+use nom::sequence::{pair, terminated};
 
-#[derive(Debug, PartialEq, Eq)]
-enum Packet {
-    Integer(u8),
-    List(Vec<Packet>),
-}
-
-fn compare_lists(a: &Vec<Packet>, b: &Vec<Packet>) -> Ordering {
-    for i in 0..max(a.len(), b.len()) {
-        if i >= a.len() {
-            return Ordering::Less;
-        }
-
-        if i >= b.len() {
-            return Ordering::Greater;
-        }
-
-        if a[i] < b[i] {
-            return Ordering::Less;
-        }
-
-        if a[i] > b[i] {
-            return Ordering::Greater;
-        }
-    }
-
-    return Ordering::Equal;
-}
-
-impl PartialOrd for Packet {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Packet::Integer(a), Packet::Integer(b)) => a.cmp(b),
-            (Packet::List(a), Packet::List(b)) => compare_lists(a, b),
-            (Packet::Integer(a), Packet::List(b)) => compare_lists(&vec![Packet::Integer(*a)], b),
-            (Packet::List(a), Packet::Integer(b)) => compare_lists(a, &vec![Packet::Integer(*b)]),
-        }
-    }
-}
+type Packet = NestedList<u8>;
 
 type Input = Vec<(Packet, Packet)>;
 
-fn packet_parser(input: &str) -> IResult<&str, Packet> {
-    alt((
-        map(delimited(tag("["), separated_list0(tag(","), packet_parser), tag("]")), |v| Packet::List(v)),
-        map(u8, |v| Packet::Integer(v)),
-    ))(input)
+fn parser(input: &str) -> IResult<&str, Input> {
+    separated_list0(tag("\n"), pair(terminated(Packet::parser, tag("\n")), terminated(Packet::parser, tag("\n"))))(input)
 }
 
-fn parser(input: &str) -> IResult<&str, Input> {
-    separated_list0(tag("\n"), pair(terminated(packet_parser, tag("\n")), terminated(packet_parser, tag("\n"))))(input)
+fn dividers() -> [Packet; 2] {
+    [
+        Packet::List(vec![Packet::List(vec![Packet::Item(2)])]),
+        Packet::List(vec![Packet::List(vec![Packet::Item(6)])]),
+    ]
 }
 
 fn solve_part1(input: &Input) -> usize {
     input
         .iter()
         .enumerate()
-        .filter(|(_, (a, b))| a < b)
+        .filter(|(_, (a, b))| a.compare(b).is_lt())
         .map(|(i, _)| i + 1)
         .sum()
 }
 
 fn solve_part2(input: &Input) -> usize {
-    let dividers = [
-        Packet::List(vec![Packet::Integer(2)]),
-        Packet::List(vec![Packet::Integer(6)]),
-    ];
-
-    input
-        .iter()
-        .map(|(a, b)| [a, b])
-        .flatten()
-        .chain(dividers.iter())
-        .sorted()
-        .enumerate()
-        .filter(|(_, p)| dividers.contains(p))
-        .map(|(i, _)| i + 1)
+    divider_indices(input.iter().flat_map(|(a, b)| [a, b]), &dividers())
+        .into_iter()
         .product()
 }
 
@@ -101,6 +42,12 @@ fn main() {
 
     println!("{}", solve_part1(&parsed));
     println!("{}", solve_part2(&parsed));
+
+    if std::env::args().nth(1).as_deref() == Some("--compare") {
+        for (i, (a, b)) in parsed.iter().enumerate() {
+            println!("== Pair {} ==\n{}\n{}\n{:?}\n", i + 1, a, b, a.compare(b));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -118,16 +65,56 @@ mod tests {
         assert_eq!(parser("[1,1,3,1,1]\n[1,1,5,1,1]\n\n[[1],[2,3,4]]\n[[1],4]\n"), Ok(("",
         vec![
             (
-                Packet::List(vec![Packet::Integer(1),Packet::Integer(1),Packet::Integer(3),Packet::Integer(1),Packet::Integer(1)]),
-                Packet::List(vec![Packet::Integer(1),Packet::Integer(1),Packet::Integer(5),Packet::Integer(1),Packet::Integer(1)]),
+                Packet::List(vec![Packet::Item(1),Packet::Item(1),Packet::Item(3),Packet::Item(1),Packet::Item(1)]),
+                Packet::List(vec![Packet::Item(1),Packet::Item(1),Packet::Item(5),Packet::Item(1),Packet::Item(1)]),
             ),
             (
-                Packet::List(vec![Packet::List(vec![Packet::Integer(1)]),Packet::List(vec![Packet::Integer(2),Packet::Integer(3),Packet::Integer(4)])]),
-                Packet::List(vec![Packet::List(vec![Packet::Integer(1)]),Packet::Integer(4)]),
+                Packet::List(vec![Packet::List(vec![Packet::Item(1)]),Packet::List(vec![Packet::Item(2),Packet::Item(3),Packet::Item(4)])]),
+                Packet::List(vec![Packet::List(vec![Packet::Item(1)]),Packet::Item(4)]),
             ),
         ])));
     }
 
+    #[test]
+    fn test_from_str() {
+        let packet: Packet = " [1, [2,[3, []]] ,4 ]\n".parse().unwrap();
+
+        assert_eq!(packet.to_string(), "[1,[2,[3,[]]],4]");
+        assert_eq!(packet.depth(), 4);
+        assert_eq!(packet.items(), vec![&1, &2, &3, &4]);
+        assert!("[1,[2]".parse::<Packet>().is_err());
+        assert!("[1,x]".parse::<Packet>().is_err());
+
+        let words: NestedList<String> = "[a,[b,c]]".parse().unwrap();
+
+        assert!(words.compare(&"[a,[b,d]]".parse().unwrap()).is_lt());
+    }
+
+    #[test]
+    fn test_display() {
+        for line in INPUT.lines().filter(|line| !line.is_empty()) {
+            assert_eq!(line.parse::<Packet>().unwrap().to_string(), line);
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let packet = |input: &str| input.parse::<Packet>().unwrap();
+
+        assert!(packet("[[1],[2,3,4]]").compare(&packet("[[1],4]")).is_lt());
+        assert!(packet("[9]").compare(&packet("[[8,7,6]]")).is_gt());
+        assert!(packet("[[[]]]").compare(&packet("[[]]")).is_gt());
+        assert!(packet("[[2]]").compare(&packet("[2]")).is_eq());
+        assert_ne!(packet("[[2]]"), packet("[2]"));
+    }
+
+    #[test]
+    fn test_divider_indices() {
+        let packets = parsed_input();
+
+        assert_eq!(divider_indices(packets.iter().flat_map(|(a, b)| [a, b]), &dividers()), vec![10, 14]);
+    }
+
     #[test]
     fn test_solve_part1() {
         assert_eq!(solve_part1(&parsed_input()), 13);
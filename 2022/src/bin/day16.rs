@@ -1,5 +1,5 @@
 use advent_of_code_2022::{read, parse};
-use itertools::Itertools;
+use advent_of_code_2022::valve_network::{NetworkError, ValveNetwork};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{alpha1, u8};
@@ -7,7 +7,7 @@ use nom::combinator::map;
 use nom::IResult;
 use nom::multi::{separated_list1, many0};
 use nom::sequence::{preceded, terminated};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 #[derive(Debug, PartialEq)]
 struct Valve<'a> {
@@ -47,136 +47,46 @@ fn parser(input: &str) -> IResult<&str, Input> {
     Ok((input, HashMap::from_iter(pairs)))
 }
 
-#[derive(Debug)]
-struct World {
-    valves: HashMap<String, u8>,
-    distances: HashMap<String, HashMap<String, u8>>,
+fn network(input: &Input) -> Result<ValveNetwork, NetworkError> {
+    ValveNetwork::new("AA", input.iter().map(|(id, valve)| (*id, valve.flow_rate as u32, valve.to.as_slice())))
 }
 
-impl<'a> From<Input<'a>> for World {
-    fn from(input: Input) -> Self {
-        let valves: HashMap<String, u8> = input
-            .iter()
-            .filter(|(_, valve)| valve.flow_rate != 0)
-            .map(|(id, valve)| ((*id).to_owned(), valve.flow_rate))
-            .collect();
-
-        let nodes: Vec<String> = input.keys().map(|k| (*k).to_owned()).collect();
-        let mut distances: HashMap<String, HashMap<String, u8>> = HashMap::new();
-
-        for (id, valve) in input.iter() {
-            let id = (*id).to_owned();
-
-            let mut map: HashMap<String, u8> = valve.to.iter().map(|id| ((*id).to_owned(), 1)).collect();
-
-            map.insert(id.clone(), 0);
-
-            distances.insert(id, map);
-        }
-
-        for k in nodes.iter() {
-            for i in nodes.iter() {
-                for j in nodes.iter() {
-                    if let (Some(dist_i_k), Some(dist_k_j)) = (distances.get(i).unwrap().get(k), distances.get(k).unwrap().get(j)) {
-                        let sum = dist_i_k + dist_k_j;
-                        if let Some(dist_i_j) = distances.get(i).unwrap().get(j) {
-                            if *dist_i_j > sum {
-                                distances.get_mut(i).unwrap().insert(j.to_owned(), sum);
-                            }
-                        } else {
-                            distances.get_mut(i).unwrap().insert(j.to_owned(), sum);
-                        }
-                    }
-                }
-            }
-        }
-
-        for i in nodes.iter() {
-            if *i != "AA" && valves.get(i) == None {
-                distances.remove(i);
-            } else {
-                let distances_to = distances.get_mut(i).unwrap();
-
-                for j in nodes.iter() {
-                    if i==j || valves.get(j) == None {
-                        distances_to.remove(j);
-                    }
-                }
-            }
-        }
-
-        Self {
-            valves,
-            distances,
-        }
-    }
+fn solve_part1(network: &ValveNetwork) -> u32 {
+    network.best_pressure(30, 1)
 }
 
-fn compute_flow(world: &World, valves_to_check: &HashSet<&str>, remaining_time: u8, valve_id: &str) -> u16 {
-    valves_to_check
-        .iter()
-        .filter_map(|to| {
-            let time_needed = *world.distances.get(valve_id).unwrap().get(*to).unwrap() + 1;
-
-            if time_needed > remaining_time {
-                return None;
-            }
-
-            let remaining_time = remaining_time - time_needed;
-
-            let flow_rate = *world.valves.get(*to).unwrap();
-            let flow = flow_rate as u16 * remaining_time as u16;
-
-            let mut valves_to_check = valves_to_check.clone();
-
-            valves_to_check.remove(to);
-
-            Some(flow + compute_flow(world, &valves_to_check, remaining_time, to))
-        })
-        .max()
-        .unwrap_or(0)
-}
-
-fn solve_part1(world: &World) -> u16 {
-    let valves: Vec<String> = world.valves.keys().cloned().collect();
-    let valves_set: HashSet<&str> = valves.iter().map(|s| &s as &str).collect();
-
-    compute_flow(&world, &valves_set, 30, "AA")
-}
-
-fn solve_part2(world: &World) -> u16 {
-    let valves: Vec<String> = world.valves.keys().cloned().collect();
-    let valves_set: HashSet<&str> = valves.iter().map(|s| &s as &str).collect();
-
-    let mut best = 0;
-
-    for valves_for_me in valves.iter().powerset() {
-        let valves_for_me: HashSet<&str> = valves_for_me.iter().map(|s| &s as &str).collect();
-        let valves_for_elephant: HashSet<&str> = valves_set.difference(&valves_for_me).cloned().collect();
-
-        let current = compute_flow(&world, &valves_for_me, 26, "AA") + compute_flow(&world, &valves_for_elephant, 26, "AA");
-
-        if current > best {
-            best = current;
-        }
-    }
-
-    best
+fn solve_part2(network: &ValveNetwork) -> u32 {
+    network.best_pressure(26, 2)
 }
 
 fn main() {
     let input = read(16);
 
     let parsed = parse(parser, &input);
-    let world = World::from(parsed);
+    let network = match network(&parsed) {
+        Ok(network) => network,
+        Err(error) => {
+            println!("Invalid network: {}", error);
+            return;
+        },
+    };
+
+    if std::env::args().nth(1).as_deref() == Some("--explain") {
+        println!("{}\n", network.plan(30, 1));
+        println!("{}", network.plan(26, 2));
+
+        return;
+    }
 
-    println!("{}", solve_part1(&world));
-    println!("{}", solve_part2(&world));
+    println!("{}", solve_part1(&network));
+    println!("{}", solve_part2(&network));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code_2022::valve_network::{Opening, Schedule, MAX_VALVES};
+    use itertools::Itertools;
 
     const INPUT: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
 Valve BB has flow rate=13; tunnels lead to valves CC, AA
@@ -210,13 +120,72 @@ Valve JJ has flow rate=21; tunnel leads to valve II
         assert_eq!(parser(INPUT), Ok(("", parsed_input())));
     }
 
+    fn valves(schedule: &Schedule) -> Vec<&str> {
+        schedule.openings.iter().map(|opening| opening.valve.as_str()).sorted().collect()
+    }
+
+    #[test]
+    fn test_network() {
+        let network = network(&parsed_input()).unwrap();
+
+        assert_eq!(network.len(), 6);
+        assert_eq!(network.pressures(30)[0b111111], 1651);
+        assert_eq!(network.plan(30, 0).pressure, 0);
+        assert_eq!(network.best_pressure(30, 0), 0);
+
+        let schedule = network.schedule(30, 0b100100);
+
+        assert_eq!(schedule.openings, vec![
+            Opening { valve: "DD".to_owned(), minute: 2, pressure: 560 },
+            Opening { valve: "JJ".to_owned(), minute: 6, pressure: 504 },
+        ]);
+        assert_eq!(schedule.pressure, 1064);
+    }
+
+    #[test]
+    fn test_plan() {
+        let network = network(&parsed_input()).unwrap();
+
+        let plan = network.plan(30, 1);
+        let minutes: Vec<u32> = plan.schedules[0].openings.iter().map(|opening| opening.minute).collect();
+
+        assert_eq!(plan.pressure, 1651);
+        assert_eq!(minutes, vec![2, 5, 9, 17, 21, 24]);
+
+        let plan = network.plan(26, 2);
+        let mut split: Vec<Vec<&str>> = plan.schedules.iter().map(valves).collect();
+        split.sort();
+
+        assert_eq!(split, vec![vec!["BB", "CC", "JJ"], vec!["DD", "EE", "HH"]]);
+        assert_eq!(plan.schedules.iter().map(|schedule| schedule.pressure).sum::<u32>(), plan.pressure);
+
+        assert!(network.plan(26, 3).pressure >= plan.pressure);
+        assert_eq!(network.best_pressure(26, 3), network.plan(26, 3).pressure);
+    }
+
+    #[test]
+    fn test_network_errors() {
+        let mut input = parsed_input();
+
+        input.get_mut("HH").unwrap().to.push("ZZ");
+        assert_eq!(network(&input).unwrap_err(), NetworkError::UnknownValve("ZZ".to_owned()));
+
+        assert_eq!(ValveNetwork::new("ZZ", [("AA", 0, &[][..])]).unwrap_err(), NetworkError::UnknownValve("ZZ".to_owned()));
+
+        let names: Vec<String> = (0..=MAX_VALVES).map(|i| format!("V{}", i)).collect();
+        let tunnels = ["AA"];
+        let valves = names.iter().map(|name| (name.as_str(), 1, &tunnels[..])).chain([("AA", 0, &tunnels[..])]);
+
+        assert_eq!(ValveNetwork::new("AA", valves).unwrap_err(), NetworkError::TooManyValves(MAX_VALVES + 1));
+    }
+
     #[test]
     fn test_solve_part1() {
-        assert_eq!(solve_part1(&World::from(parsed_input())), 1651);
+        assert_eq!(solve_part1(&network(&parsed_input()).unwrap()), 1651);
     }
 
     #[test]
     fn test_solve_part2() {
-        assert_eq!(solve_part2(&World::from(parsed_input())), 1707);
+        assert_eq!(solve_part2(&network(&parsed_input()).unwrap()), 1707);
     }
 }
@@ -1,4 +1,5 @@
 use advent_of_code_2022::{read, parse};
+use advent_of_code_2022::cycle::find_cycle;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::combinator::value;
@@ -6,6 +7,7 @@ use nom::IResult;
 use nom::multi:: many0;
 use nom::sequence::terminated;
 use std::cmp::max;
+use std::iter::successors;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum Direction {
@@ -95,61 +97,96 @@ const WIDTHS: [usize; 5] = [
     2,
 ];
 
-fn intersect<const HEIGHT: usize>(map: &[[bool; HEIGHT]; 7], rock: usize, x: usize, y: usize) -> bool {
-    for rx in 0..WIDTHS[rock] {
-        for ry in 0..HEIGHTS[rock] {
-            if ROCKS[rock][rx][ry] && map[x + rx][y + ry] {
-                return true;
-            }
+/// Number of rows kept at the top of the tower, falling rocks never getting deeper than that
+const WINDOW: usize = 64;
+
+/// Top of the tower, the rows below the window being only counted in `trimmed` so that the
+/// chamber stays small enough to be cloned on every rock
+#[derive(Clone, Debug)]
+struct Chamber {
+    rows: Vec<[bool; 7]>,
+    trimmed: usize,
+    rock: usize,
+    jet: usize,
+}
+
+impl Chamber {
+    fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            trimmed: 0,
+            rock: 0,
+            jet: 0,
         }
     }
 
-    return false
-}
+    fn height(&self) -> usize {
+        self.trimmed + self.rows.len()
+    }
 
-fn solve_part1(input: &Input) -> usize {
-    let mut cycle: usize = 0;
-    let mut rock: usize = 0;
-    let mut height: usize = 0;
+    fn intersect(&self, rock: usize, x: usize, y: usize) -> bool {
+        for rx in 0..WIDTHS[rock] {
+            for ry in 0..HEIGHTS[rock] {
+                if ROCKS[rock][rx][ry] && self.rows.get(y + ry).is_some_and(|row| row[x + rx]) {
+                    return true;
+                }
+            }
+        }
 
-    let mut map = [[false; 6572]; 7];
+        false
+    }
+
+    fn dropped(&self, input: &Input) -> Self {
+        let mut chamber = self.clone();
+        let rock = chamber.rock;
 
-    for _ in 0..2022 {
         let mut x: usize = 2;
-        let mut y: usize = height + 3;
+        let mut y: usize = chamber.rows.len() + 3;
 
         loop {
             // Lateral movement
-            let movable = match input[cycle] {
+            let movable = match input[chamber.jet] {
                 Direction::Left => x > 0,
                 Direction::Right => x + WIDTHS[rock] < 7,
             };
 
             if movable {
-                let new_x = match input[cycle] {
+                let new_x = match input[chamber.jet] {
                     Direction::Left => x - 1,
                     Direction::Right => x + 1,
                 };
 
-                if !intersect(&map, rock, new_x, y) {
+                if !chamber.intersect(rock, new_x, y) {
                     x = new_x;
                 }
             }
 
-            cycle += 1;
-            cycle %= input.len();
+            chamber.jet += 1;
+            chamber.jet %= input.len();
+
+            // Down movement, the bottom of the window only being the floor while nothing was trimmed
+            if y == 0 {
+                assert!(chamber.trimmed == 0, "A rock fell through the {} rows kept at the top of the tower", WINDOW);
+            }
+
+            if y == 0 || chamber.intersect(rock, x, y - 1) {
+                let height = max(chamber.rows.len(), y + HEIGHTS[rock]);
+                chamber.rows.resize(height, [false; 7]);
 
-            // Down movement
-            if y == 0 || intersect(&map, rock, x, y - 1) {
                 for rx in 0..WIDTHS[rock] {
                     for ry in 0..HEIGHTS[rock] {
                         if ROCKS[rock][rx][ry] {
-                            map[x + rx][y + ry] = true;
+                            chamber.rows[y + ry][x + rx] = true;
                         }
                     }
                 }
 
-                height = max(height, y + HEIGHTS[rock]);
+                if chamber.rows.len() > WINDOW {
+                    let excess = chamber.rows.len() - WINDOW;
+
+                    chamber.rows.drain(..excess);
+                    chamber.trimmed += excess;
+                }
 
                 break;
             } else {
@@ -157,15 +194,43 @@ fn solve_part1(input: &Input) -> usize {
             }
         }
 
-        rock += 1;
-        rock %= ROCKS.len();
+        chamber.rock += 1;
+        chamber.rock %= ROCKS.len();
+
+        chamber
+    }
+
+    /// Depth of the highest rock of each column from the top of the tower, at most the window size
+    fn skyline(&self) -> [usize; 7] {
+        let mut skyline = [self.rows.len(); 7];
+
+        for (x, depth) in skyline.iter_mut().enumerate() {
+            if let Some(top) = self.rows.iter().rposition(|row| row[x]) {
+                *depth = self.rows.len() - top;
+            }
+        }
+
+        skyline
+    }
+
+    fn key(&self) -> (usize, usize, [usize; 7]) {
+        (self.rock, self.jet, self.skyline())
     }
+}
 
-    height
+fn heights(input: &Input) -> impl Iterator<Item = usize> + '_ {
+    successors(Some(Chamber::new()), |chamber| Some(chamber.dropped(input))).map(|chamber| chamber.height())
+}
+
+fn solve_part1(input: &Input) -> usize {
+    heights(input).nth(2022).unwrap()
 }
 
 fn solve_part2(input: &Input) -> usize {
-    0
+    let cycle = find_cycle(&Chamber::new(), |chamber| chamber.dropped(input), Chamber::key);
+    let heights: Vec<usize> = heights(input).take(cycle.start + cycle.period + 1).collect();
+
+    cycle.extrapolate(1000000000000, |rocks| heights[rocks] as i64) as usize
 }
 
 fn main() {
@@ -234,6 +299,24 @@ mod tests {
         assert_eq!(parser(INPUT), Ok(("", parsed_input())));
     }
 
+    #[test]
+    fn test_window() {
+        let input = parsed_input();
+        let chamber = successors(Some(Chamber::new()), |chamber| Some(chamber.dropped(&input))).nth(2022).unwrap();
+
+        assert_eq!(chamber.rows.len(), WINDOW);
+        assert_eq!(chamber.height(), 3068);
+    }
+
+    #[test]
+    #[should_panic(expected = "fell through")]
+    fn test_fell_through_window() {
+        // Rows were trimmed below an empty window, nothing stops the rock at its bottom
+        let chamber = Chamber { rows: vec![[false; 7]; WINDOW], trimmed: 10, rock: 0, jet: 0 };
+
+        chamber.dropped(&parsed_input());
+    }
+
     #[test]
     fn test_solve_part1() {
         assert_eq!(solve_part1(&parsed_input()), 3068);
@@ -1,3 +1,4 @@
+use advent_of_code_2022::branch_and_bound::{self, Problem, Solution};
 use advent_of_code_2022::{read, parse};
 use nom::bytes::complete::tag;
 use nom::character::complete::u8;
@@ -88,171 +89,133 @@ fn parser(input: &str) -> IResult<&str, Input> {
     many0(terminated(blueprint_parser, tag("\n")))(input)
 }
 
-#[derive(PartialEq, Eq)]
-struct Factory<'a> {
-    blueprint: &'a Blueprint,
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
 
-    ore_robots: u8,
-    clay_robots: u8,
-    obsidian_robots: u8,
-    geode_robots: u16,
+impl Blueprint {
+    /// Ore, clay and obsidian needed to build each kind of robot
+    pub fn costs(&self) -> [[u16; 3]; 4] {
+        [
+            [self.ore_robot_ore_cost as u16, 0, 0],
+            [self.clay_robot_ore_cost as u16, 0, 0],
+            [self.obsidian_robot_ore_cost as u16, self.obsidian_robot_clay_cost as u16, 0],
+            [self.geode_robot_ore_cost as u16, 0, self.geode_robot_obsidian_cost as u16],
+        ]
+    }
 
-    ore: u8,
-    clay: u8,
-    obsidian: u8,
-    geodes: u16,
+    /// More robots than that of a given kind would produce resources faster than they can be spent
+    pub fn max_robots(&self) -> [u16; 3] {
+        [self.max_ore_cost() as u16, self.max_clay_cost() as u16, self.max_obsidian_cost() as u16]
+    }
 }
 
-impl<'a> Factory<'a> {
-    pub fn new(blueprint: &'a Blueprint) -> Self {
-        Self {
-            blueprint,
-
-            ore_robots: 1,
-            clay_robots: 0,
-            obsidian_robots: 0,
-            geode_robots: 0,
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Factory {
+    remaining_time: u16,
+    robots: [u16; 4],
+    resources: [u16; 4],
+}
 
-            ore: 0,
-            clay: 0,
-            obsidian: 0,
-            geodes: 0,
-        }
+impl Factory {
+    /// Geodes opened by the end if no more robots are built
+    pub fn final_geodes(&self) -> u16 {
+        self.resources[GEODE] + self.robots[GEODE] * self.remaining_time
     }
 
-    pub fn next_states(&self) -> Vec<Factory<'a>> {
-        let mut states = Vec::new();
-        let mut can_wait_ressources = false;
-
-        if self.ore >= self.blueprint.geode_robot_ore_cost && self.obsidian >= self.blueprint.geode_robot_obsidian_cost {
-            states.push(Factory {
-                blueprint: self.blueprint,
-
-                ore_robots: self.ore_robots,
-                clay_robots: self.clay_robots,
-                obsidian_robots: self.obsidian_robots,
-                geode_robots: self.geode_robots + 1,
-
-                ore: self.ore - self.blueprint.geode_robot_ore_cost + self.ore_robots,
-                clay: self.clay + self.clay_robots,
-                obsidian: self.obsidian - self.blueprint.geode_robot_obsidian_cost + self.obsidian_robots,
-                geodes: self.geodes + self.geode_robots,
-            });
-
-            return states;
-        } else {
-            if self.obsidian_robots > 0 {
-                can_wait_ressources = true;
-            }
-        }
+    /// Waits until the robot can be afforded then builds it, if it can be done in time to be useful
+    pub fn build(&self, blueprint: &Blueprint, robot: usize) -> Option<Factory> {
+        let costs = blueprint.costs()[robot];
+        let mut wait = 0;
 
-        if self.ore_robots < self.blueprint.max_ore_cost() {
-            if self.ore >= self.blueprint.ore_robot_ore_cost {
-                states.push(Factory {
-                    blueprint: self.blueprint,
-
-                    ore_robots: self.ore_robots + 1,
-                    clay_robots: self.clay_robots,
-                    obsidian_robots: self.obsidian_robots,
-                    geode_robots: self.geode_robots,
-
-                    ore: self.ore - self.blueprint.ore_robot_ore_cost + self.ore_robots,
-                    clay: self.clay + self.clay_robots,
-                    obsidian: self.obsidian + self.obsidian_robots,
-                    geodes: self.geodes + self.geode_robots,
-                });
-            } else {
-                can_wait_ressources = true;
+        for resource in [ORE, CLAY, OBSIDIAN] {
+            let missing = costs[resource].saturating_sub(self.resources[resource]);
+
+            if missing > 0 {
+                if self.robots[resource] == 0 {
+                    return None;
+                }
+
+                wait = wait.max(missing.div_ceil(self.robots[resource]));
             }
         }
 
-        if self.clay_robots < self.blueprint.max_clay_cost() {
-            if self.ore >= self.blueprint.clay_robot_ore_cost {
-                states.push(Factory {
-                    blueprint: self.blueprint,
-
-                    ore_robots: self.ore_robots,
-                    clay_robots: self.clay_robots + 1,
-                    obsidian_robots: self.obsidian_robots,
-                    geode_robots: self.geode_robots,
-
-                    ore: self.ore - self.blueprint.clay_robot_ore_cost  + self.ore_robots,
-                    clay: self.clay + self.clay_robots,
-                    obsidian: self.obsidian + self.obsidian_robots,
-                    geodes: self.geodes + self.geode_robots,
-                });
-            } else {
-                can_wait_ressources = true;
-            }
+        let elapsed = wait + 1;
+
+        if elapsed >= self.remaining_time {
+            return None;
         }
 
-        if self.obsidian_robots < self.blueprint.max_obsidian_cost() {
-            if self.ore >= self.blueprint.obsidian_robot_ore_cost && self.clay >= self.blueprint.obsidian_robot_clay_cost {
-                states.push(Factory {
-                    blueprint: self.blueprint,
-
-                    ore_robots: self.ore_robots,
-                    clay_robots: self.clay_robots,
-                    obsidian_robots: self.obsidian_robots + 1,
-                    geode_robots: self.geode_robots,
-
-                    ore: self.ore - self.blueprint.obsidian_robot_ore_cost + self.ore_robots,
-                    clay: self.clay - self.blueprint.obsidian_robot_clay_cost + self.clay_robots,
-                    obsidian: self.obsidian + self.obsidian_robots,
-                    geodes: self.geodes + self.geode_robots,
-                });
-            } else {
-                if self.clay_robots > 0 {
-                    can_wait_ressources = true;
-                }
-            }
+        let mut next = self.clone();
+
+        next.remaining_time -= elapsed;
+        for resource in [ORE, CLAY, OBSIDIAN, GEODE] {
+            next.resources[resource] += self.robots[resource] * elapsed;
         }
+        for resource in [ORE, CLAY, OBSIDIAN] {
+            next.resources[resource] -= costs[resource];
+        }
+        next.robots[robot] += 1;
 
-        if can_wait_ressources {
-            states.push(Factory {
-                blueprint: self.blueprint,
+        Some(next)
+    }
+}
 
-                ore_robots: self.ore_robots,
-                clay_robots: self.clay_robots,
-                obsidian_robots: self.obsidian_robots,
-                geode_robots: self.geode_robots,
+struct GeodeCracking<'a> {
+    blueprint: &'a Blueprint,
+    minutes: u16,
+}
 
-                ore: self.ore + self.ore_robots,
-                clay: self.clay + self.clay_robots,
-                obsidian: self.obsidian + self.obsidian_robots,
-                geodes: self.geodes + self.geode_robots,
-            });
+impl Problem for GeodeCracking<'_> {
+    type State = Factory;
+    type Score = u16;
+
+    fn state(&self) -> Factory {
+        Factory {
+            remaining_time: self.minutes,
+            robots: [1, 0, 0, 0],
+            resources: [0; 4],
         }
+    }
 
-        states
+    fn successors(&self, factory: &Factory) -> Vec<Factory> {
+        let max_robots = self.blueprint.max_robots();
+
+        [GEODE, OBSIDIAN, CLAY, ORE].into_iter()
+            .filter(|&robot| robot == GEODE || factory.robots[robot] < max_robots[robot])
+            .filter_map(|robot| factory.build(self.blueprint, robot))
+            .collect()
+    }
+
+    /// As if a geode robot could be built every remaining minute
+    fn upper_bound(&self, factory: &Factory) -> u16 {
+        let remaining_time = factory.remaining_time;
+
+        factory.final_geodes() + remaining_time * remaining_time.saturating_sub(1) / 2
     }
-}
 
-fn get_best_geodes_count(factory: Factory, remaining_time: u8) -> u16 {
-    if remaining_time == 0 {
-        return factory.geodes;
+    fn score(&self, factory: &Factory) -> u16 {
+        factory.final_geodes()
     }
+}
 
-    factory
-        .next_states()
-        .into_iter()
-        .map(|next_state| get_best_geodes_count(next_state, remaining_time - 1))
-        .max()
-        .unwrap()
+fn get_best_geodes_count(blueprint: &Blueprint, minutes: u16) -> Solution<Factory, u16> {
+    branch_and_bound::solve(&GeodeCracking { blueprint, minutes })
 }
 
 fn solve_part1(input: &Input) -> u16 {
     input
-        .into_iter()
-        .map(|blueprint| get_best_geodes_count(Factory::new(blueprint), 24) * blueprint.id as u16)
+        .iter()
+        .map(|blueprint| get_best_geodes_count(blueprint, 24).score * blueprint.id as u16)
         .sum()
 }
 
 fn solve_part2(input: &Input) -> u16 {
     input
-        .into_iter()
+        .iter()
         .take(3)
-        .map(|blueprint| get_best_geodes_count(Factory::new(blueprint), 32))
+        .map(|blueprint| get_best_geodes_count(blueprint, 32).score)
         .product()
 }
 
@@ -261,6 +224,22 @@ fn main() {
 
     let parsed = parse(parser, &input);
 
+    if std::env::args().nth(1).as_deref() == Some("--stats") {
+        for blueprint in &parsed {
+            for minutes in [24, 32] {
+                let solution = get_best_geodes_count(blueprint, minutes);
+                let statistics = solution.statistics;
+
+                println!(
+                    "Blueprint {} in {} minutes: {} geodes, {} expanded, {} memoised, {} pruned",
+                    blueprint.id, minutes, solution.score, statistics.expanded, statistics.memoised, statistics.pruned,
+                );
+            }
+        }
+
+        return;
+    }
+
     println!("{}", solve_part1(&parsed));
     println!("{}", solve_part2(&parsed));
 }
@@ -285,6 +264,25 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
         assert_eq!(parser(INPUT), Ok(("", parsed_input())));
     }
 
+    #[test]
+    fn test_get_best_geodes_count() {
+        let blueprint = Blueprint::new(1, 4, 2, 3, 14, 2, 7);
+        let solution = get_best_geodes_count(&blueprint, 24);
+
+        assert_eq!(solution.score, 9);
+        assert_eq!(solution.state.robots[GEODE], 2);
+        assert!(solution.statistics.pruned > 0);
+
+        let initial = Factory { remaining_time: 24, robots: [1, 0, 0, 0], resources: [0; 4] };
+
+        assert_eq!(initial.build(&blueprint, OBSIDIAN), None);
+        assert_eq!(initial.build(&blueprint, CLAY), Some(Factory {
+            remaining_time: 21,
+            robots: [1, 1, 0, 0],
+            resources: [1, 0, 0, 0],
+        }));
+    }
+
     #[test]
     fn test_solve_part1() {
         assert_eq!(solve_part1(&parsed_input()), 33);
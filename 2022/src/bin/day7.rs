@@ -1,17 +1,20 @@
 use advent_of_code_2022::{read, parse};
 use nom::branch::alt;
 use nom::bytes::complete::{is_not, tag};
-use nom::character::complete::{alpha1, u32};
-use nom::combinator::map;
+use nom::character::complete::u32;
+use nom::combinator::{map, not};
 use nom::IResult;
-use nom::multi::many1;
-use nom::sequence::{preceded, separated_pair, terminated};
-use std::collections::HashMap;
+use nom::multi::{many0, many1};
+use nom::sequence::{delimited, preceded, separated_pair, terminated};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::fmt;
 
 #[derive(Debug, PartialEq)]
 enum Instruction {
     Cd(Move),
     Ls(Vec<Entry>),
+    Other(String),
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,30 +53,30 @@ enum EntryKind {
 }
 
 fn cd_parser(input: &str) -> IResult<&str, Move> {
-    terminated(
-        alt((
-            map(tag("$ cd /"), |_| Move::Root),
-            map(tag("$ cd .."), |_| Move::Up),
-            map(preceded(tag("$ cd "), alpha1), |dir: &str| Move::Dir(dir.to_owned())),
-        )),
-        tag("\n"),
+    map(
+        delimited(tag("$ cd "), is_not("\n"), tag("\n")),
+        |target: &str| match target {
+            "/" => Move::Root,
+            ".." => Move::Up,
+            path => Move::Dir(path.to_owned()),
+        },
     )(input)
 }
 
 fn ls_parser(input: &str) -> IResult<&str, Vec<Entry>> {
     preceded(
         tag("$ ls\n"),
-        many1(
+        many0(
             terminated(
                 alt((
                     map(preceded(
                         tag("dir "),
-                        alpha1,
+                        is_not("\n"),
                     ), |name: &str| Entry::dir(name.to_owned())),
                     map(separated_pair(
                         u32,
                         tag(" "),
-                        is_not(" \n"),
+                        is_not("\n"),
                     ), |(size, name): (u32, &str)| Entry::file(name.to_owned(), size)),
                 )),
                 tag("\n"),
@@ -82,124 +85,267 @@ fn ls_parser(input: &str) -> IResult<&str, Vec<Entry>> {
     )(input)
 }
 
+/// Any other command, along with its output which is ignored
+fn other_parser(input: &str) -> IResult<&str, String> {
+    terminated(
+        map(delimited(tag("$ "), is_not("\n"), tag("\n")), |command: &str| command.to_owned()),
+        many0(terminated(preceded(not(tag("$")), is_not("\n")), tag("\n"))),
+    )(input)
+}
+
 fn parser(input: &str) -> IResult<&str, Vec<Instruction>> {
     many1(
         alt((
             map(cd_parser, |cd| Instruction::Cd(cd)),
             map(ls_parser, |ls| Instruction::Ls(ls)),
+            map(other_parser, Instruction::Other),
         )),
     )(input)
 }
 
 #[derive(Debug, PartialEq)]
-struct Dir {
-    files: HashMap<String, DirEntry>,
+enum NodeKind {
+    Dir(BTreeMap<String, usize>),
+    File(u32),
 }
 
-impl Dir {
-    pub fn new() -> Self {
-        Dir {
-            files: HashMap::new(),
-        }
-    }
+#[derive(Debug, PartialEq)]
+struct Node {
+    name: String,
+    parent: Option<usize>,
+    kind: NodeKind,
+}
 
-    pub fn size(&self) -> u32 {
-        self.files.iter().map(|(_, entry)| entry.size()).sum()
-    }
+/// Entry of the filesystem found by a query
+#[derive(Clone, Debug, PartialEq)]
+struct FsEntry {
+    path: String,
+    size: u32,
+    is_dir: bool,
 }
 
-#[derive(Debug, PartialEq)]
-enum DirEntry {
-    Dir(Dir),
-    File(u32),
+/// Transcript operations that conflict with what was listed before
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum FsError {
+    /// Entering or creating something inside a file
+    NotADirectory(String),
+    /// A file listed where a directory already exists, or the other way around
+    KindMismatch(String),
 }
 
-impl DirEntry {
-    pub fn size(&self) -> u32 {
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            DirEntry::Dir(dir) => dir.size(),
-            DirEntry::File(size) => *size,
+            FsError::NotADirectory(path) => write!(f, "{}: not a directory", path),
+            FsError::KindMismatch(path) => write!(f, "{}: listed both as a file and a directory", path),
         }
     }
 }
 
-fn build_tree(instructions: &[Instruction]) -> Dir {
-    let mut dir = Dir::new();
-    let mut current: Vec<String> = Vec::new();
+/// Filesystem tree rebuilt from a terminal transcript, directories sizes are computed once and
+/// cached until the next change
+#[derive(Debug)]
+struct VirtualFs {
+    nodes: Vec<Node>,
+    sizes: OnceCell<Vec<u32>>,
+}
+
+impl VirtualFs {
+    const ROOT: usize = 0;
 
-    for instruction in instructions {
-        match instruction {
-            Instruction::Cd(cd) => match cd {
-                Move::Root => {
-                    current = Vec::new();
-                },
-                Move::Up => {
-                    current.pop();
-                },
-                Move::Dir(name) => {
-                    current.push(name.to_owned());
-                },
-            },
-            Instruction::Ls(entries) => {
-                let mut current_dir = Box::new(&mut dir);
+    pub fn new() -> Self {
+        VirtualFs {
+            nodes: vec![Node { name: String::new(), parent: None, kind: NodeKind::Dir(BTreeMap::new()) }],
+            sizes: OnceCell::new(),
+        }
+    }
 
-                for path in &current {
-                    *current_dir = match current_dir.files.get_mut(path).unwrap() {
-                        DirEntry::Dir(d) => d,
-                        DirEntry::File(_) => panic!("Here be dragons"),
+    pub fn from_transcript(instructions: &[Instruction]) -> Result<Self, FsError> {
+        let mut fs = VirtualFs::new();
+        let mut current = VirtualFs::ROOT;
+
+        for instruction in instructions {
+            match instruction {
+                Instruction::Cd(Move::Root) => current = VirtualFs::ROOT,
+                Instruction::Cd(Move::Up) => current = fs.nodes[current].parent.unwrap_or(VirtualFs::ROOT),
+                Instruction::Cd(Move::Dir(path)) => current = fs.mkdir(current, path)?,
+                Instruction::Ls(entries) => {
+                    for entry in entries {
+                        match entry.kind {
+                            EntryKind::Dir => {
+                                fs.mkdir(current, &entry.name)?;
+                            },
+                            EntryKind::File(size) => fs.add_file(current, &entry.name, size)?,
+                        }
                     }
-                }
+                },
+                Instruction::Other(_) => {},
+            }
+        }
 
-                for entry in entries {
-                    current_dir.files.insert(entry.name.clone(), match entry.kind {
-                        EntryKind::Dir => DirEntry::Dir(Dir::new()),
-                        EntryKind::File(size) => DirEntry::File(size),
-                    });
-                }
+        Ok(fs)
+    }
+
+    fn children(&self, node: usize) -> Option<&BTreeMap<String, usize>> {
+        match &self.nodes[node].kind {
+            NodeKind::Dir(children) => Some(children),
+            NodeKind::File(_) => None,
+        }
+    }
+
+    /// Index of the entry named `name` in `dir`, created with `kind` if it doesn't exist yet
+    fn insert(&mut self, dir: usize, name: &str, kind: NodeKind) -> Result<usize, FsError> {
+        let children = self.children(dir).ok_or_else(|| FsError::NotADirectory(self.path(dir)))?;
+
+        if let Some(&existing) = children.get(name) {
+            return match (&self.nodes[existing].kind, &kind) {
+                (NodeKind::Dir(_), NodeKind::Dir(_)) | (NodeKind::File(_), NodeKind::File(_)) => Ok(existing),
+                _ => Err(FsError::KindMismatch(self.path(existing))),
+            };
+        }
+
+        let index = self.nodes.len();
+
+        self.nodes.push(Node { name: name.to_owned(), parent: Some(dir), kind });
+        self.sizes = OnceCell::new();
+
+        if let NodeKind::Dir(children) = &mut self.nodes[dir].kind {
+            children.insert(name.to_owned(), index);
+        }
+
+        Ok(index)
+    }
+
+    /// Creates every missing directory along the path, absolute or relative to `dir`
+    pub fn mkdir(&mut self, dir: usize, path: &str) -> Result<usize, FsError> {
+        let start = if path.starts_with('/') { VirtualFs::ROOT } else { dir };
+
+        path.split('/').filter(|name| !name.is_empty() && *name != ".").try_fold(start, |current, name| {
+            if name == ".." {
+                Ok(self.nodes[current].parent.unwrap_or(VirtualFs::ROOT))
+            } else {
+                self.insert(current, name, NodeKind::Dir(BTreeMap::new()))
+            }
+        })
+    }
+
+    /// Adds a file to `dir`, or updates its size if it was already listed
+    pub fn add_file(&mut self, dir: usize, name: &str, size: u32) -> Result<(), FsError> {
+        let file = self.insert(dir, name, NodeKind::File(size))?;
+
+        if let NodeKind::File(existing) = &mut self.nodes[file].kind {
+            if *existing != size {
+                *existing = size;
+                self.sizes = OnceCell::new();
             }
         }
+
+        Ok(())
     }
 
-    dir
-}
+    pub fn lookup(&self, path: &str) -> Option<usize> {
+        path.split('/').filter(|name| !name.is_empty()).try_fold(VirtualFs::ROOT, |current, name| {
+            self.children(current)?.get(name).copied()
+        })
+    }
+
+    pub fn path(&self, node: usize) -> String {
+        match self.nodes[node].parent {
+            None => "/".to_owned(),
+            Some(VirtualFs::ROOT) => format!("/{}", self.nodes[node].name),
+            Some(parent) => format!("{}/{}", self.path(parent), self.nodes[node].name),
+        }
+    }
+
+    pub fn size(&self, node: usize) -> u32 {
+        self.sizes.get_or_init(|| {
+            let mut sizes = vec![0; self.nodes.len()];
+
+            // Children are always created after their parent
+            for (index, node) in self.nodes.iter().enumerate().rev() {
+                if let NodeKind::File(size) = node.kind {
+                    sizes[index] = size;
+                }
+
+                if let Some(parent) = node.parent {
+                    sizes[parent] += sizes[index];
+                }
+            }
 
-fn solve_part1(input: &Dir) -> u32 {
-    let mut sum = 0;
+            sizes
+        })[node]
+    }
 
-    if input.size() <= 100000 {
-        sum += input.size();
+    fn entry(&self, node: usize) -> FsEntry {
+        FsEntry {
+            path: self.path(node),
+            size: self.size(node),
+            is_dir: self.children(node).is_some(),
+        }
     }
 
-    for (_, entry) in &input.files {
-        if let DirEntry::Dir(dir) = entry {
-            sum += solve_part1(dir);
+    /// Every entry below `node` (included), children before their parent like `du` does
+    fn walk(&self, node: usize, entries: &mut Vec<FsEntry>) {
+        for &child in self.children(node).into_iter().flat_map(|children| children.values()) {
+            self.walk(child, entries);
         }
+
+        entries.push(self.entry(node));
     }
 
-    sum
-}
+    pub fn find(&self, predicate: impl Fn(&FsEntry) -> bool) -> Vec<FsEntry> {
+        let mut entries = Vec::new();
+
+        self.walk(VirtualFs::ROOT, &mut entries);
+
+        entries.into_iter().filter(|entry| predicate(entry)).collect()
+    }
+
+    /// Size and path of every directory below `node` (included)
+    pub fn du(&self, node: usize) -> String {
+        let mut entries = Vec::new();
 
-fn find_candidate(input: &Dir, needed: u32, candidate: u32) -> u32 {
-    let mut candidate = candidate;
+        self.walk(node, &mut entries);
 
-    if input.size() > needed && input.size() < candidate {
-        candidate = input.size();
+        entries.into_iter()
+            .filter(|entry| entry.is_dir)
+            .map(|entry| format!("{}\t{}\n", entry.size, entry.path))
+            .collect()
     }
 
-    for (_, entry) in &input.files {
-        if let DirEntry::Dir(dir) = entry {
-            candidate = find_candidate(dir, needed, candidate);
+    fn render(&self, node: usize, depth: usize, output: &mut String) {
+        let name = if node == VirtualFs::ROOT { "/" } else { &self.nodes[node].name };
+
+        match &self.nodes[node].kind {
+            NodeKind::Dir(children) => {
+                *output += &format!("{}- {} (dir)\n", "  ".repeat(depth), name);
+
+                for &child in children.values() {
+                    self.render(child, depth + 1, output);
+                }
+            },
+            NodeKind::File(size) => *output += &format!("{}- {} (file, size={})\n", "  ".repeat(depth), name, size),
         }
     }
 
-    candidate
+    pub fn tree(&self) -> String {
+        let mut output = String::new();
+
+        self.render(VirtualFs::ROOT, 0, &mut output);
+
+        output
+    }
+}
+
+fn solve_part1(input: &VirtualFs) -> u32 {
+    input.find(|entry| entry.is_dir && entry.size <= 100000).iter().map(|entry| entry.size).sum()
 }
 
-fn solve_part2(input: &Dir) -> u32 {
-    let remaining = 70000000 - input.size();
-    let needed = 30000000 - remaining;
+fn solve_part2(input: &VirtualFs) -> u32 {
+    let remaining = 70000000 - input.size(VirtualFs::ROOT);
+    let needed = 30000000u32.saturating_sub(remaining);
 
-    find_candidate(input, needed, 70000000)
+    input.find(|entry| entry.is_dir && entry.size >= needed).iter().map(|entry| entry.size).min().unwrap()
 }
 
 fn main() {
@@ -207,10 +353,29 @@ fn main() {
 
     let parsed = parse(parser, &input);
 
-    let tree = build_tree(&parsed);
-
-    println!("{}", solve_part1(&tree));
-    println!("{}", solve_part2(&tree));
+    let fs = match VirtualFs::from_transcript(&parsed) {
+        Ok(fs) => fs,
+        Err(error) => {
+            println!("Invalid transcript: {}", error);
+            return;
+        },
+    };
+
+    println!("{}", solve_part1(&fs));
+    println!("{}", solve_part2(&fs));
+
+    match std::env::args().nth(1).as_deref() {
+        Some("--tree") => print!("{}", fs.tree()),
+        Some("--du") => {
+            let path = std::env::args().nth(2).unwrap_or("/".to_owned());
+
+            match fs.lookup(&path) {
+                Some(node) => print!("{}", fs.du(node)),
+                None => println!("{}: no such file or directory", path),
+            }
+        },
+        _ => {},
+    }
 }
 
 #[cfg(test)]
@@ -250,122 +415,112 @@ mod tests {
         ])));
     }
 
+    const EXAMPLE: &str = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n$ ls\ndir e\n29116 f\n2557 g\n62596 h.lst\n$ cd e\n$ ls\n584 i\n$ cd ..\n$ cd ..\n$ cd d\n$ ls\n4060174 j\n8033020 d.log\n5626152 d.ext\n7214296 k\n";
+
+    fn example() -> VirtualFs {
+        VirtualFs::from_transcript(&parse(parser, EXAMPLE)).unwrap()
+    }
+
     #[test]
-    fn test_build_tree() {
-        assert_eq!(build_tree(&vec![
-            Instruction::Cd(Move::Root),
-            Instruction::Ls(vec![
-                Entry::dir("a".to_owned()),
-                Entry::file("b.txt".to_owned(), 14848514),
-                Entry::file("c.dat".to_owned(), 8504156),
-                Entry::dir("d".to_owned()),
-            ]),
-            Instruction::Cd(Move::Dir("a".to_owned())),
-            Instruction::Ls(vec![
-                Entry::dir("e".to_owned()),
-                Entry::file("f".to_owned(), 29116),
-                Entry::file("g".to_owned(), 2557),
-                Entry::file("h.lst".to_owned(), 62596),
-            ]),
-            Instruction::Cd(Move::Dir("e".to_owned())),
-            Instruction::Ls(vec![
-                Entry::file("i".to_owned(), 584),
-            ]),
-            Instruction::Cd(Move::Up),
+    fn test_parser_extra_commands() {
+        assert_eq!(parser("$ cd /a/b c\n$ ls\n$ pwd\n/a/b c\n$ cd ..\n"), Ok(("", vec![
+            Instruction::Cd(Move::Dir("/a/b c".to_owned())),
+            Instruction::Ls(vec![]),
+            Instruction::Other("pwd".to_owned()),
             Instruction::Cd(Move::Up),
-            Instruction::Cd(Move::Dir("d".to_owned())),
-            Instruction::Ls(vec![
-                Entry::file("j".to_owned(), 4060174),
-                Entry::file("d.log".to_owned(), 8033020),
-                Entry::file("d.ext".to_owned(), 5626152),
-                Entry::file("k".to_owned(), 7214296),
-            ]),
-        ]), Dir {
-            files: HashMap::from([
-                ("a".to_owned(), DirEntry::Dir(Dir {
-                    files: HashMap::from([
-                        ("e".to_owned(), DirEntry::Dir(Dir {
-                            files: HashMap::from([
-                                ("i".to_owned(), DirEntry::File(584)),
-                            ]),
-                        })),
-                        ("f".to_owned(), DirEntry::File(29116)),
-                        ("g".to_owned(), DirEntry::File(2557)),
-                        ("h.lst".to_owned(), DirEntry::File(62596)),
-                    ]),
-                })),
-                ("b.txt".to_owned(), DirEntry::File(14848514)),
-                ("c.dat".to_owned(), DirEntry::File(8504156)),
-                ("d".to_owned(), DirEntry::Dir(Dir {
-                    files: HashMap::from([
-                        ("j".to_owned(), DirEntry::File(4060174)),
-                        ("d.log".to_owned(), DirEntry::File(8033020)),
-                        ("d.ext".to_owned(), DirEntry::File(5626152)),
-                        ("k".to_owned(), DirEntry::File(7214296)),
-                    ]),
-                })),
-            ]),
-        })
+        ])));
+    }
+
+    #[test]
+    fn test_tree() {
+        assert_eq!(example().tree(), "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+");
+    }
+
+    #[test]
+    fn test_du() {
+        assert_eq!(example().du(VirtualFs::ROOT), "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n");
+    }
+
+    #[test]
+    fn test_find() {
+        let fs = example();
+
+        assert_eq!(fs.find(|entry| !entry.is_dir && entry.size > 8000000), vec![
+            FsEntry { path: "/b.txt".to_owned(), size: 14848514, is_dir: false },
+            FsEntry { path: "/c.dat".to_owned(), size: 8504156, is_dir: false },
+            FsEntry { path: "/d/d.log".to_owned(), size: 8033020, is_dir: false },
+        ]);
+        assert_eq!(fs.lookup("/a/e").map(|node| fs.size(node)), Some(584));
+        assert_eq!(fs.lookup("/a").map(|node| fs.du(node)), Some("584\t/a/e\n94853\t/a\n".to_owned()));
+        assert_eq!(fs.lookup("/a/x"), None);
+    }
+
+    #[test]
+    fn test_from_transcript_variations() {
+        let fs = VirtualFs::from_transcript(&parse(parser, "$ cd /x/y\n$ ls\n10 f\n$ ls\n10 f\n5 g\n$ cd ../../z\n$ ls\n1 h\n$ cd ..\n$ cd ..\n$ ls\ndir x\n")).unwrap();
+
+        assert_eq!(fs.du(VirtualFs::ROOT), "15\t/x/y\n15\t/x\n1\t/z\n16\t/\n");
+    }
+
+    #[test]
+    fn test_changed_size() {
+        let mut fs = VirtualFs::from_transcript(&parse(parser, "$ cd /a\n$ ls\n10 f\n")).unwrap();
+
+        assert_eq!(fs.du(VirtualFs::ROOT), "10\t/a\n10\t/\n");
+
+        let a = fs.lookup("/a").unwrap();
+        fs.add_file(a, "f", 25).unwrap();
+
+        assert_eq!(fs.du(VirtualFs::ROOT), "25\t/a\n25\t/\n");
+
+        let fs = VirtualFs::from_transcript(&parse(parser, "$ cd /a\n$ ls\n10 f\n$ ls\n25 f\n")).unwrap();
+
+        assert_eq!(fs.find(|entry| entry.path == "/a/f")[0].size, 25);
+        assert_eq!(fs.size(VirtualFs::ROOT), 25);
+    }
+
+    #[test]
+    fn test_from_transcript_conflicts() {
+        assert_eq!(
+            VirtualFs::from_transcript(&parse(parser, "$ ls\n10 f\n$ cd f\n")).unwrap_err(),
+            FsError::KindMismatch("/f".to_owned()),
+        );
+        assert_eq!(
+            VirtualFs::from_transcript(&parse(parser, "$ ls\n10 f\n$ cd f/g\n")).unwrap_err(),
+            FsError::KindMismatch("/f".to_owned()),
+        );
+        assert_eq!(
+            VirtualFs::from_transcript(&parse(parser, "$ ls\ndir d\n$ ls\n5 d\n")).unwrap_err(),
+            FsError::KindMismatch("/d".to_owned()),
+        );
+
+        let mut fs = example();
+        let file = fs.lookup("/b.txt").unwrap();
+
+        assert_eq!(fs.add_file(file, "x", 1), Err(FsError::NotADirectory("/b.txt".to_owned())));
     }
 
     #[test]
     fn test_solve_part1() {
-        assert_eq!(solve_part1(&Dir {
-            files: HashMap::from([
-                ("a".to_owned(), DirEntry::Dir(Dir {
-                    files: HashMap::from([
-                        ("e".to_owned(), DirEntry::Dir(Dir {
-                            files: HashMap::from([
-                                ("i".to_owned(), DirEntry::File(584)),
-                            ]),
-                        })),
-                        ("f".to_owned(), DirEntry::File(29116)),
-                        ("g".to_owned(), DirEntry::File(2557)),
-                        ("h.lst".to_owned(), DirEntry::File(62596)),
-                    ]),
-                })),
-                ("b.txt".to_owned(), DirEntry::File(14848514)),
-                ("c.dat".to_owned(), DirEntry::File(8504156)),
-                ("d".to_owned(), DirEntry::Dir(Dir {
-                    files: HashMap::from([
-                        ("j".to_owned(), DirEntry::File(4060174)),
-                        ("d.log".to_owned(), DirEntry::File(8033020)),
-                        ("d.ext".to_owned(), DirEntry::File(5626152)),
-                        ("k".to_owned(), DirEntry::File(7214296)),
-                    ]),
-                })),
-            ]),
-        }), 95437);
+        assert_eq!(solve_part1(&example()), 95437);
     }
 
     #[test]
     fn test_solve_part2() {
-        assert_eq!(solve_part2(&Dir {
-            files: HashMap::from([
-                ("a".to_owned(), DirEntry::Dir(Dir {
-                    files: HashMap::from([
-                        ("e".to_owned(), DirEntry::Dir(Dir {
-                            files: HashMap::from([
-                                ("i".to_owned(), DirEntry::File(584)),
-                            ]),
-                        })),
-                        ("f".to_owned(), DirEntry::File(29116)),
-                        ("g".to_owned(), DirEntry::File(2557)),
-                        ("h.lst".to_owned(), DirEntry::File(62596)),
-                    ]),
-                })),
-                ("b.txt".to_owned(), DirEntry::File(14848514)),
-                ("c.dat".to_owned(), DirEntry::File(8504156)),
-                ("d".to_owned(), DirEntry::Dir(Dir {
-                    files: HashMap::from([
-                        ("j".to_owned(), DirEntry::File(4060174)),
-                        ("d.log".to_owned(), DirEntry::File(8033020)),
-                        ("d.ext".to_owned(), DirEntry::File(5626152)),
-                        ("k".to_owned(), DirEntry::File(7214296)),
-                    ]),
-                })),
-            ]),
-        }), 24933642);
+        assert_eq!(solve_part2(&example()), 24933642);
     }
-
 }
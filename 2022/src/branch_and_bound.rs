@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// A maximisation problem explored depth first, where every state has a score it could stop at
+/// and an upper bound on the score of anything reachable from it.
+pub trait Problem {
+    type State: Clone + Eq + Hash;
+    type Score: Copy + Ord;
+
+    /// Initial state of the search
+    fn state(&self) -> Self::State;
+
+    fn successors(&self, state: &Self::State) -> Vec<Self::State>;
+
+    /// Score that can't be beaten by `state` or any of its successors
+    fn upper_bound(&self, state: &Self::State) -> Self::Score;
+
+    /// Score obtained by stopping at `state`
+    fn score(&self, state: &Self::State) -> Self::Score;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// States whose successors were generated
+    pub expanded: usize,
+    /// States skipped because they were already explored
+    pub memoised: usize,
+    /// States skipped because their upper bound couldn't beat the best score found so far
+    pub pruned: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Solution<S, V> {
+    pub score: V,
+    pub state: S,
+    pub statistics: Statistics,
+}
+
+struct Search<'a, P: Problem> {
+    problem: &'a P,
+    best: (P::Score, P::State),
+    seen: HashSet<P::State>,
+    statistics: Statistics,
+}
+
+impl<P: Problem> Search<'_, P> {
+    fn explore(&mut self, state: P::State) {
+        if self.seen.contains(&state) {
+            self.statistics.memoised += 1;
+            return;
+        }
+
+        if self.problem.upper_bound(&state) <= self.best.0 {
+            self.statistics.pruned += 1;
+            return;
+        }
+
+        // Marked before its successors are explored, so that cycles leading back to it stop here
+        self.seen.insert(state.clone());
+
+        let score = self.problem.score(&state);
+        if score > self.best.0 {
+            self.best = (score, state.clone());
+        }
+
+        self.statistics.expanded += 1;
+
+        for successor in self.problem.successors(&state) {
+            self.explore(successor);
+        }
+    }
+}
+
+/// Best score reachable from the initial state of `problem`, along with the state reaching it
+pub fn solve<P: Problem>(problem: &P) -> Solution<P::State, P::Score> {
+    let initial = problem.state();
+
+    let mut search = Search {
+        problem,
+        best: (problem.score(&initial), initial.clone()),
+        seen: HashSet::new(),
+        statistics: Statistics::default(),
+    };
+
+    search.explore(initial);
+
+    Solution {
+        score: search.best.0,
+        state: search.best.1,
+        statistics: search.statistics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scores ten times the node, `0` and `3` leading to each other and `1` to the dead end `2`
+    struct Graph;
+
+    impl Problem for Graph {
+        type State = u8;
+        type Score = u32;
+
+        fn state(&self) -> u8 {
+            0
+        }
+
+        fn successors(&self, state: &u8) -> Vec<u8> {
+            match state {
+                0 => vec![3, 1],
+                1 => vec![2],
+                3 => vec![0],
+                _ => vec![],
+            }
+        }
+
+        fn upper_bound(&self, state: &u8) -> u32 {
+            match state {
+                1 | 2 => 20,
+                _ => 30,
+            }
+        }
+
+        fn score(&self, state: &u8) -> u32 {
+            *state as u32 * 10
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let solution = solve(&Graph);
+
+        assert_eq!((solution.score, solution.state), (30, 3));
+        assert_eq!(solution.statistics, Statistics { expanded: 2, memoised: 1, pruned: 1 });
+    }
+}
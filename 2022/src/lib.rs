@@ -3,6 +3,19 @@ use nom::error::Error;
 use nom::{Finish, Parser};
 use std::fs::read_to_string;
 
+pub mod branch_and_bound;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/cycle.rs"]
+pub mod cycle;
+pub mod nested_list;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/number_theory.rs"]
+pub mod number_theory;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/ocr.rs"]
+pub mod ocr;
+pub mod valve_network;
+
 pub fn read(day: u8) -> String {
     read_to_string(format!("input/day{}.txt", day)).expect("Failed to read input file")
 }
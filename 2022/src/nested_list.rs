@@ -0,0 +1,142 @@
+use nom::branch::alt;
+use nom::bytes::complete::{is_not, tag};
+use nom::character::complete::{multispace0, space0};
+use nom::combinator::{all_consuming, map, map_res};
+use nom::multi::separated_list0;
+use nom::sequence::{delimited, preceded};
+use nom::{Finish, IResult};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Arbitrarily nested lists like `[1,[2,[]],3]`. Equality is structural, the puzzle ordering being
+/// available through [`NestedList::compare`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NestedList<T> {
+    Item(T),
+    List(Vec<NestedList<T>>),
+}
+
+impl<T> NestedList<T> {
+    pub fn depth(&self) -> usize {
+        match self {
+            NestedList::Item(_) => 0,
+            NestedList::List(items) => 1 + items.iter().map(NestedList::depth).max().unwrap_or(0),
+        }
+    }
+
+    /// Every item, in the order they are written
+    pub fn items(&self) -> Vec<&T> {
+        match self {
+            NestedList::Item(item) => vec![item],
+            NestedList::List(items) => items.iter().flat_map(NestedList::items).collect(),
+        }
+    }
+}
+
+impl<T> NestedList<T> where T: Ord {
+    /// Puzzle ordering, item by item with a lone item comparing like a list containing only it,
+    /// which makes `2`, `[2]` and `[[2]]` all compare equal
+    pub fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (NestedList::Item(a), NestedList::Item(b)) => a.cmp(b),
+            (NestedList::List(a), NestedList::List(b)) => Self::compare_lists(a, b),
+            (NestedList::Item(_), NestedList::List(b)) => Self::compare_lists(std::slice::from_ref(self), b),
+            (NestedList::List(a), NestedList::Item(_)) => Self::compare_lists(a, std::slice::from_ref(other)),
+        }
+    }
+
+    fn compare_lists(a: &[Self], b: &[Self]) -> Ordering {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| a.compare(b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len()))
+    }
+}
+
+impl<T> NestedList<T> where T: FromStr {
+    /// Parses JSON-like text, allowing spaces around items and brackets
+    pub fn parser(input: &str) -> IResult<&str, Self> {
+        delimited(
+            space0,
+            alt((
+                map(
+                    delimited(tag("["), separated_list0(tag(","), Self::parser), preceded(space0, tag("]"))),
+                    NestedList::List,
+                ),
+                map(map_res(is_not(",[] \t\r\n"), str::parse), NestedList::Item),
+            )),
+            space0,
+        )(input)
+    }
+}
+
+impl<T> From<T> for NestedList<T> {
+    fn from(value: T) -> Self {
+        NestedList::Item(value)
+    }
+}
+
+impl<T> From<Vec<NestedList<T>>> for NestedList<T> {
+    fn from(value: Vec<NestedList<T>>) -> Self {
+        NestedList::List(value)
+    }
+}
+
+impl<T> FromStr for NestedList<T> where T: FromStr {
+    type Err = nom::error::Error<String>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match all_consuming(delimited(multispace0, Self::parser, multispace0))(s).finish() {
+            Ok((_, list)) => Ok(list),
+            Err(error) => Err(nom::error::Error::new(error.input.to_owned(), error.code)),
+        }
+    }
+}
+
+impl<T> fmt::Display for NestedList<T> where T: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NestedList::Item(item) => write!(f, "{}", item),
+            NestedList::List(items) => {
+                write!(f, "[")?;
+
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, "{}", item)?;
+                }
+
+                write!(f, "]")
+            },
+        }
+    }
+}
+
+/// Position (starting at 1) each divider would have once sorted along with the packets
+pub fn divider_indices<'a, T>(packets: impl IntoIterator<Item = &'a NestedList<T>>, dividers: &[NestedList<T>]) -> Vec<usize>
+where
+    T: Ord + 'a,
+{
+    let packets: Vec<&NestedList<T>> = packets.into_iter().collect();
+
+    dividers.iter()
+        .enumerate()
+        .map(|(i, divider)| {
+            let before_packets = packets.iter().filter(|packet| packet.compare(divider).is_lt()).count();
+            let before_dividers = dividers.iter()
+                .enumerate()
+                .filter(|(j, other)| match other.compare(divider) {
+                    Ordering::Less => true,
+                    Ordering::Equal => *j < i,
+                    Ordering::Greater => false,
+                })
+                .count();
+
+            before_packets + before_dividers + 1
+        })
+        .collect()
+}
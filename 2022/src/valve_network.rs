@@ -0,0 +1,252 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Most valves worth opening a network can have. Sets of valves are bitmasks of their indices,
+/// indexing tables of `2^n` pressures, and splitting them between agents walks through `3^n`
+/// pairs of subsets.
+pub const MAX_VALVES: usize = 24;
+
+/// Tunnel network reduced to the valves worth opening, with the travel time between each of
+/// them. There can't be more than [`MAX_VALVES`] of them.
+#[derive(Clone, Debug)]
+pub struct ValveNetwork {
+    names: Vec<String>,
+    flow_rates: Vec<u32>,
+    /// Travel time between valves, the last row being from the starting point
+    distances: Vec<Vec<u32>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Opening {
+    pub valve: String,
+    /// Minute during which the valve is opened, it releases pressure from the next one
+    pub minute: u32,
+    /// Total pressure released by the valve until the end
+    pub pressure: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schedule {
+    pub pressure: u32,
+    pub openings: Vec<Opening>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub pressure: u32,
+    pub schedules: Vec<Schedule>,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (agent, schedule) in self.schedules.iter().enumerate() {
+            writeln!(f, "Agent {} ({} pressure):", agent + 1, schedule.pressure)?;
+
+            for opening in &schedule.openings {
+                writeln!(f, "  minute {}: open {} (+{})", opening.minute, opening.valve, opening.pressure)?;
+            }
+        }
+
+        write!(f, "Total: {} pressure", self.pressure)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetworkError {
+    /// The start or the target of a tunnel isn't among the valves
+    UnknownValve(String),
+    /// More valves worth opening than [`MAX_VALVES`]
+    TooManyValves(usize),
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::UnknownValve(name) => write!(f, "unknown valve {}", name),
+            NetworkError::TooManyValves(count) => write!(f, "{} valves worth opening, at most {} are supported", count, MAX_VALVES),
+        }
+    }
+}
+
+impl ValveNetwork {
+    /// Builds the network from `(name, flow rate, tunnels)` for every valve, agents starting at
+    /// `start`. Fails if a valve is missing or if more than [`MAX_VALVES`] have a flow rate.
+    pub fn new<'a>(start: &str, valves: impl IntoIterator<Item = (&'a str, u32, &'a [&'a str])>) -> Result<Self, NetworkError> {
+        let valves: HashMap<&str, (u32, &[&str])> = valves.into_iter().map(|(name, rate, to)| (name, (rate, to))).collect();
+
+        let unknown = std::iter::once(start)
+            .chain(valves.values().flat_map(|(_, to)| to.iter().copied()))
+            .find(|name| !valves.contains_key(name));
+
+        if let Some(name) = unknown {
+            return Err(NetworkError::UnknownValve(name.to_owned()));
+        }
+
+        let mut names: Vec<&str> = valves.iter().filter(|(_, (rate, _))| *rate > 0).map(|(name, _)| *name).collect();
+        names.sort_unstable();
+
+        if names.len() > MAX_VALVES {
+            return Err(NetworkError::TooManyValves(names.len()));
+        }
+
+        let distances = names.iter()
+            .chain([&start])
+            .map(|from| {
+                let mut reached = HashMap::from([(*from, 0)]);
+                let mut queue = VecDeque::from([*from]);
+
+                while let Some(current) = queue.pop_front() {
+                    for next in valves[current].1 {
+                        if !reached.contains_key(next) {
+                            reached.insert(next, reached[current] + 1);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+
+                names.iter().map(|to| reached.get(to).copied().unwrap_or(u32::MAX)).collect()
+            })
+            .collect();
+
+        Ok(Self {
+            flow_rates: names.iter().map(|name| valves[name].0).collect(),
+            names: names.into_iter().map(str::to_owned).collect(),
+            distances,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    fn all(&self) -> usize {
+        (1 << self.len()) - 1
+    }
+
+    fn start(&self) -> usize {
+        self.len()
+    }
+
+    /// Valves reachable and worth opening from `from` with `remaining` minutes left, along with
+    /// the minutes left once they're open
+    fn reachable(&self, from: usize, remaining: u32, allowed: usize) -> impl Iterator<Item = (usize, u32)> + '_ {
+        (0..self.len())
+            .filter(move |valve| allowed & (1 << valve) != 0)
+            .filter_map(move |valve| remaining.checked_sub(self.distances[from][valve].saturating_add(1)).map(|left| (valve, left)))
+            .filter(|&(_, left)| left > 0)
+    }
+
+    fn visit(&self, from: usize, remaining: u32, opened: usize, pressure: u32, best: &mut [u32]) {
+        best[opened] = best[opened].max(pressure);
+
+        for (valve, left) in self.reachable(from, remaining, self.all() & !opened).collect::<Vec<_>>() {
+            self.visit(valve, left, opened | 1 << valve, pressure + self.flow_rates[valve] * left, best);
+        }
+    }
+
+    /// Best pressure a single agent can release by opening exactly the valves of each mask
+    pub fn pressures(&self, minutes: u32) -> Vec<u32> {
+        let mut best = vec![0; 1 << self.len()];
+
+        self.visit(self.start(), minutes, 0, 0, &mut best);
+
+        best
+    }
+
+    /// Best schedule of a single agent only allowed to open the valves of `mask`
+    pub fn schedule(&self, minutes: u32, mask: usize) -> Schedule {
+        self.best_schedule(self.start(), minutes, mask, minutes)
+    }
+
+    fn best_schedule(&self, from: usize, remaining: u32, allowed: usize, minutes: u32) -> Schedule {
+        self.reachable(from, remaining, allowed)
+            .map(|(valve, left)| {
+                let mut schedule = self.best_schedule(valve, left, allowed & !(1 << valve), minutes);
+                let pressure = self.flow_rates[valve] * left;
+
+                schedule.pressure += pressure;
+                schedule.openings.insert(0, Opening { valve: self.names[valve].clone(), minute: minutes - left, pressure });
+
+                schedule
+            })
+            .max_by_key(|schedule| schedule.pressure)
+            .unwrap_or_default()
+    }
+
+    /// `tables[k]` is the best pressure of `k + 1` agents sharing the valves of each mask, each
+    /// opening any subset of them
+    fn tables(&self, minutes: u32, agents: usize) -> Vec<Vec<u32>> {
+        let mut single = self.pressures(minutes);
+        for valve in 0..self.len() {
+            for mask in 0..single.len() {
+                if mask & (1 << valve) != 0 {
+                    single[mask] = single[mask].max(single[mask ^ (1 << valve)]);
+                }
+            }
+        }
+
+        let mut tables = vec![single];
+        for _ in 1..agents {
+            let (single, previous) = (&tables[0], tables.last().unwrap());
+            let table = (0..single.len())
+                .map(|mask| submasks(mask).map(|part| single[part] + previous[mask ^ part]).max().unwrap())
+                .collect();
+
+            tables.push(table);
+        }
+
+        tables
+    }
+
+    /// Best pressure `agents` agents working simultaneously can release, each opening different valves
+    pub fn best_pressure(&self, minutes: u32, agents: usize) -> u32 {
+        match agents {
+            0 => 0,
+            _ => self.tables(minutes, agents)[agents - 1][self.all()],
+        }
+    }
+
+    /// Same as [`Self::best_pressure`], along with the schedule of each agent
+    pub fn plan(&self, minutes: u32, agents: usize) -> Plan {
+        if agents == 0 {
+            return Plan { pressure: 0, schedules: Vec::new() };
+        }
+
+        let tables = self.tables(minutes, agents);
+        let single = &tables[0];
+
+        let mut masks = Vec::new();
+        let mut remaining = self.all();
+
+        for table in tables[..agents - 1].iter().rev() {
+            let part = submasks(remaining).max_by_key(|&part| single[part] + table[remaining ^ part]).unwrap();
+
+            masks.push(part);
+            remaining ^= part;
+        }
+        masks.push(remaining);
+
+        let schedules: Vec<Schedule> = masks.into_iter().map(|mask| self.schedule(minutes, mask)).collect();
+
+        Plan {
+            pressure: tables[agents - 1][self.all()],
+            schedules,
+        }
+    }
+}
+
+/// Every subset of `mask`, including the empty one and `mask` itself
+fn submasks(mask: usize) -> impl Iterator<Item = usize> {
+    let mut next = Some(mask);
+
+    std::iter::from_fn(move || {
+        let current = next?;
+        next = if current == 0 { None } else { Some((current - 1) & mask) };
+
+        Some(current)
+    })
+}
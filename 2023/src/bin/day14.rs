@@ -1,6 +1,4 @@
-use std::{collections::HashMap, ops::Rem};
-
-use advent_of_code_2023::{read, Parsable, util::{Grid, Point}};
+use advent_of_code_2023::{read, Parsable, util::{Grid, Point, cycle::nth_state}};
 use nom::{IResult, combinator::value, combinator::map, branch::alt, bytes::complete::tag, multi::many0, sequence::terminated};
 
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
@@ -183,42 +181,7 @@ fn solve_part1(input: &Input) -> usize {
 }
 
 fn solve_part2(input: &Input) -> usize {
-    let cycles = 1000000000;
-
-    let mut map = HashMap::new();
-    let mut map_rev = HashMap::new();
-
-    let mut loop_start = None;
-    let mut loop_end = None;
-
-    let mut current = input.clone();
-
-    for i in 0..cycles {
-        map.insert(current.clone(), i);
-        map_rev.insert(i, current.clone());
-
-        let tilted = current.tilted_cycle();
-
-        if let Some(&start) = map.get(&tilted) {
-            loop_start = Some(start);
-            loop_end = Some(i + 1);
-
-            break;
-        }
-
-        current = tilted;
-    }
-
-    let loop_start = loop_start.unwrap();
-    let loop_end = loop_end.unwrap();
-    let loop_size = loop_end - loop_start;
-
-    let remaining_cycles = cycles - loop_start;
-    let last_cycles = remaining_cycles.rem(loop_size);
-
-    let last = map_rev.get(&(loop_start + last_cycles)).unwrap();
-
-    last.load()
+    nth_state(input, Input::tilted_cycle, Input::clone, 1000000000).load()
 }
 
 fn main() {
@@ -231,6 +194,8 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use advent_of_code_2023::util::cycle::{find_cycle, find_cycle_brent, find_cycle_floyd, Cycle};
+
     use super::*;
 
     const INPUT: &str = "O....#....
@@ -352,6 +317,15 @@ O.#..O.#.#
         assert_eq!(solve_part1(&parsed_input()), 136);
     }
 
+    #[test]
+    fn test_find_cycle() {
+        let cycle = Cycle { start: 3, period: 7 };
+
+        assert_eq!(find_cycle(&parsed_input(), Input::tilted_cycle, Input::clone), cycle);
+        assert_eq!(find_cycle_floyd(&parsed_input(), Input::tilted_cycle, Input::clone), cycle);
+        assert_eq!(find_cycle_brent(&parsed_input(), Input::tilted_cycle, Input::clone), cycle);
+    }
+
     #[test]
     fn test_solve_part2() {
         assert_eq!(solve_part2(&parsed_input()), 64);
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use advent_of_code_2023::{read, Parsable};
+use advent_of_code_2023::{read, Parsable, util::number_theory::lcm};
 use nom::bytes::complete::tag;
 use nom::character::complete::anychar;
 use nom::branch::alt;
@@ -124,25 +124,6 @@ impl Parsable for Node {
     }
 }
 
-fn gcd(a: u64, b: u64) -> u64 {
-    let mut a = a;
-    let mut b = b;
-
-    while a != b {
-        if a < b {
-            (a, b) = (b, a);
-        }
-
-        (a, b) = (a - b, b);
-    }
-
-    a
-}
-
-fn lcm(a: u64, b: u64) -> u64 {
-    a * (b / gcd(a, b))
-}
-
 fn solve_part1(input: &Input) -> u64 {
     input.travel(Node::new(['A', 'A', 'A']), Node::is_end)
 }
@@ -1,5 +1,4 @@
-use advent_of_code_2023::{read, Parsable};
-use itertools::Itertools;
+use advent_of_code_2023::{read, Parsable, util::sequence::Sequence};
 use nom::bytes::complete::tag;
 use nom::character::complete::i32;
 use nom::IResult;
@@ -35,38 +34,16 @@ impl History {
         Self { history: history.into() }
     }
 
-    fn predict_next(&self) -> i32 {
-        let mut history = self.history.clone();
-        let mut lasts = Vec::new();
-
-        while !history.iter().all(|&value| value == 0) {
-            lasts.push(*history.last().unwrap());
-            history = history.iter().tuple_windows().map(|(a, b)| b - a).collect()
-        }
-
-        let mut value = 0;
-        for last in lasts.iter().rev() {
-            value = last + value;
-        }
+    fn sequence(&self) -> Sequence {
+        Sequence::new(self.history.iter().copied()).expect("i32 values always fit in i64")
+    }
 
-        value
+    fn predict_next(&self) -> i32 {
+        self.sequence().next() as i32
     }
 
     fn predict_previous(&self) -> i32 {
-        let mut history = self.history.clone();
-        let mut firsts = Vec::new();
-
-        while !history.iter().all(|&value| value == 0) {
-            firsts.push(*history.first().unwrap());
-            history = history.iter().tuple_windows().map(|(a, b)| b - a).collect()
-        }
-
-        let mut value = 0;
-        for first in firsts.iter().rev() {
-            value = first - value;
-        }
-
-        value
+        self.sequence().previous() as i32
     }
 }
 
@@ -97,6 +74,8 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use advent_of_code_2023::util::rational::Rational;
+
     use super::*;
 
     const INPUT: &str = "0 3 6 9 12 15
@@ -131,6 +110,41 @@ mod tests {
         assert_eq!(History::new([1, 3, 6, 10, 15, 21]).predict_previous(), 0);
     }
 
+    #[test]
+    fn test_degree() {
+        assert_eq!(History::new([0, 3, 6, 9, 12, 15]).sequence().degree(), Some(1));
+        assert_eq!(History::new([1, 3, 6, 10, 15, 21]).sequence().degree(), Some(2));
+        assert_eq!(History::new([10, 13, 16, 21, 30, 45]).sequence().degree(), Some(3));
+        assert_eq!(History::new([1, 2, 4, 8]).sequence().degree(), None);
+    }
+
+    #[test]
+    fn test_sequence_new() {
+        assert_eq!(Sequence::new([1u64, 3, 6]).map(|sequence| sequence.next()), Ok(10));
+        assert_eq!(Sequence::new([1usize, 3, 6]).map(|sequence| sequence.previous()), Ok(0));
+        assert_eq!(Sequence::new([1i128, 3, 6]).map(|sequence| sequence.values().to_vec()), Ok(vec![1, 3, 6]));
+        assert!(Sequence::new([1, u64::MAX]).is_err());
+        assert!(Sequence::new([i128::MIN, 0]).is_err());
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let sequence = History::new([1, 3, 6, 10, 15, 21]).sequence();
+
+        assert_eq!(sequence.extrapolate_forward(3), vec![28, 36, 45]);
+        assert_eq!(sequence.extrapolate_backward(3), vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn test_lagrange() {
+        let sequence = History::new([10, 13, 16, 21, 30, 45]).sequence();
+
+        assert_eq!(sequence.value_at(6), Rational::from(68));
+        assert_eq!(sequence.value_at(-1), Rational::from(5));
+        assert_eq!(sequence.polynomial().degree(), 3);
+        assert_eq!(sequence.polynomial().evaluate(Rational::from(26501365)), sequence.value_at(26501365));
+    }
+
     #[test]
     fn test_solve_part1() {
         assert_eq!(solve_part1(&parsed_input()), 114);
@@ -1,4 +1,14 @@
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/cycle.rs"]
+pub mod cycle;
 pub mod dijkstra;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/number_theory.rs"]
+pub mod number_theory;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/rational.rs"]
+pub mod rational;
+pub mod sequence;
 
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
 pub struct Grid<T> {
@@ -0,0 +1,175 @@
+use itertools::Itertools;
+
+use crate::util::rational::Rational;
+
+/// Integer sequence sampled at positions `0, 1, 2, ...`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sequence {
+    values: Vec<i64>,
+}
+
+impl Sequence {
+    /// Sequence of any integer values, failing with the conversion error of the first value
+    /// not fitting in an `i64`
+    pub fn new<T: TryInto<i64>>(values: impl IntoIterator<Item = T>) -> Result<Self, T::Error> {
+        Ok(Self { values: values.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()? })
+    }
+
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    /// Finite difference table, starting with the values themselves and ending with the
+    /// first row made only of zeros (or with a single value if none is reached)
+    pub fn differences(&self) -> Vec<Vec<i64>> {
+        let mut table = vec![self.values.clone()];
+
+        loop {
+            let last = table.last().unwrap();
+
+            if last.len() <= 1 || last.iter().all(|&value| value == 0) {
+                break;
+            }
+
+            let next = last.iter().tuple_windows().map(|(a, b)| b - a).collect();
+            table.push(next);
+        }
+
+        table
+    }
+
+    /// Degree of the polynomial generating the sequence, if the samples are enough to tell
+    pub fn degree(&self) -> Option<usize> {
+        let table = self.differences();
+        let last = table.last().unwrap();
+
+        if !last.iter().all(|&value| value == 0) || last.is_empty() {
+            return None;
+        }
+
+        Some(table.len().saturating_sub(2))
+    }
+
+    pub fn next(&self) -> i64 {
+        self.differences().iter().filter_map(|row| row.last()).sum()
+    }
+
+    pub fn previous(&self) -> i64 {
+        self.differences().iter().rev().filter_map(|row| row.first()).fold(0, |value, first| first - value)
+    }
+
+    pub fn extrapolate_forward(&self, count: usize) -> Vec<i64> {
+        let mut sequence = self.clone();
+
+        for _ in 0..count {
+            let next = sequence.next();
+            sequence.values.push(next);
+        }
+
+        sequence.values.split_off(self.values.len())
+    }
+
+    /// The `count` values before the first one, closest one first
+    pub fn extrapolate_backward(&self, count: usize) -> Vec<i64> {
+        let mut sequence = self.clone();
+        let mut values = Vec::new();
+
+        for _ in 0..count {
+            let previous = sequence.previous();
+            sequence.values.insert(0, previous);
+            values.push(previous);
+        }
+
+        values
+    }
+
+    fn points(&self) -> Vec<(Rational, Rational)> {
+        self.values.iter()
+            .enumerate()
+            .map(|(position, &value)| (Rational::from(position), Rational::from(value)))
+            .collect()
+    }
+
+    /// Polynomial going through every sample
+    pub fn polynomial(&self) -> Polynomial {
+        Polynomial::interpolate(&self.points())
+    }
+
+    /// Value at any position, using Lagrange interpolation over every sample
+    pub fn value_at(&self, position: i64) -> Rational {
+        lagrange(&self.points(), Rational::from(position))
+    }
+}
+
+pub fn lagrange(points: &[(Rational, Rational)], x: Rational) -> Rational {
+    points.iter()
+        .enumerate()
+        .map(|(i, &(xi, yi))| {
+            points.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(yi, |product, (_, &(xj, _))| product * (x - xj) / (xi - xj))
+        })
+        .sum()
+}
+
+/// Polynomial with exact coefficients, lowest degree first
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Polynomial {
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    pub fn new(coefficients: impl Into<Vec<Rational>>) -> Self {
+        let mut coefficients = coefficients.into();
+
+        while coefficients.last().is_some_and(Rational::is_zero) {
+            coefficients.pop();
+        }
+
+        Self { coefficients }
+    }
+
+    /// Lowest degree polynomial going through all the points
+    pub fn interpolate(points: &[(Rational, Rational)]) -> Self {
+        let mut coefficients = vec![Rational::ZERO; points.len()];
+
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            let mut basis = vec![Rational::ONE];
+            let mut denominator = Rational::ONE;
+
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+
+                let mut next = vec![Rational::ZERO; basis.len() + 1];
+                for (degree, &coefficient) in basis.iter().enumerate() {
+                    next[degree + 1] += coefficient;
+                    next[degree] -= coefficient * xj;
+                }
+
+                basis = next;
+                denominator *= xi - xj;
+            }
+
+            for (degree, coefficient) in basis.into_iter().enumerate() {
+                coefficients[degree] += coefficient * yi / denominator;
+            }
+        }
+
+        Self::new(coefficients)
+    }
+
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn evaluate(&self, x: Rational) -> Rational {
+        self.coefficients.iter().rev().fold(Rational::ZERO, |value, &coefficient| value * x + coefficient)
+    }
+}
@@ -1,4 +1,4 @@
-use advent_of_code_2024::{geometry::{Point, Vector}, parser::*, read};
+use advent_of_code_2024::{geometry::{Point, Vector}, matrix::Matrix, parser::*, rational::Rational, read};
 use nom::{bytes::complete::tag, character::complete::i64, multi::separated_list1, IResult};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -22,40 +22,14 @@ impl Machine {
     }
 
     fn get_solution(&self) -> Option<(i64, i64)> {
-        /*
-         * adx * na + bdx * nb = px
-         * ady * na + bdy * nb = py
-         *
-         * adx * bdy * na + bdx * bdy * nb = px * bdy
-         * ady * bdx * na + bdx * bdy * nb = py * bdx
-         *
-         * adx * bdy * na + bdx * bdy * nb - ady * bdx * na + bdx * bdy * nb = px * bdy - py * bdx
-         *
-         * na * (adx * bdy - ady * bdx) = px * bdy - py * bdx
-         * na = (px * bdy - py * bdx) / (adx * bdy - ady * bdx)
-         *
-         * nb = (px - adx * na) / bdx
-         */
-
-        let numerator = self.b.dx * self.prize.y - self.b.dy * self.prize.x;
-        let denominator = self.b.dx * self.a.dy - self.b.dy * self.a.dx;
-
-        if numerator % denominator == 0 {
-            let na = numerator / denominator;
-
-            let b_numerator = self.prize.x - self.a.dx * na;
-            let b_denominator = self.b.dx;
-
-            if b_numerator % b_denominator == 0 {
-                let nb = (self.prize.x - self.a.dx * na) / self.b.dx;
-
-                Some((na, nb))
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        let buttons: Matrix<Rational> = Matrix::from(vec![
+            vec![self.a.dx, self.b.dx],
+            vec![self.a.dy, self.b.dy],
+        ]);
+
+        let presses = buttons.solve(&[self.prize.x.into(), self.prize.y.into()])?.integer_solution()?;
+
+        Some((presses[0] as i64, presses[1] as i64))
     }
 }
 
@@ -1,21 +1,9 @@
-use advent_of_code_2024::{parser::*, read};
+use std::{env, fmt};
+
+use advent_of_code_2024::{device::*, parser::*, read};
 use itertools::Itertools;
 use nom::{bytes::complete::tag, multi::separated_list1, IResult};
 
-type Value = u64;
-
-#[derive(Clone, PartialEq, Eq, Debug)]
-enum Instruction {
-    Adv(u8),
-    Bxl(Value),
-    Bst(u8),
-    Jnz(usize),
-    Bxc,
-    Out(u8),
-    Bdv(u8),
-    Cdv(u8),
-}
-
 #[derive(Clone, PartialEq, Eq, Debug)]
 struct Input {
     registry_a: Value,
@@ -55,162 +43,132 @@ impl Parsable for Input {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-struct Device {
-    registry_a: Value,
-    registry_b: Value,
-    registry_c: Value,
+impl From<&Input> for Device {
+    fn from(value: &Input) -> Self {
+        Device::new(value.registry_a, value.registry_b, value.registry_c, &value.program)
+    }
+}
 
-    pointer: usize,
+fn solve_part1(input: &Input) -> Vec<Value> {
+    Device::from(input).collect()
+}
 
-    program: Vec<Instruction>,
+/// Reasons a program can't be solved by searching register A three bits at a time
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum ShapeError {
+    NotLooping,
+    ExtraJumps(usize),
+    Shift(Vec<Instruction>),
+    Outputs(usize),
+    /// More values than octal digits fit in register A
+    TooLong(usize),
+    NoSolution,
 }
 
-impl Device  {
-    fn execute_instruction(&mut self, pointer: usize) -> (usize, Option<Value>) {
-        let mut next_pointer = pointer + 1;
-        let mut output = None;
-
-        match self.program[pointer] {
-            // 0
-            Instruction::Adv(operand) => {
-                self.registry_a = self.registry_a >> self.combo(operand);
-            },
-            // 1
-            Instruction::Bxl(operand) => {
-                self.registry_b = self.registry_b ^ operand;
-            },
-            // 2
-            Instruction::Bst(operand) => {
-                self.registry_b = self.combo(operand) % 8;
-            },
-            // 3
-            Instruction::Jnz(operand) => {
-                if self.registry_a != 0 {
-                    next_pointer = operand;
-                }
-            },
-            // 4
-            Instruction::Bxc => {
-                self.registry_b = self.registry_b ^ self.registry_c;
-            },
-            // 5
-            Instruction::Out(operand) => {
-                output = Some(self.combo(operand) % 8);
-            },
-            // 6
-            Instruction::Bdv(operand) => {
-                self.registry_b = self.registry_a >> self.combo(operand);
-            },
-            // 7
-            Instruction::Cdv(operand) => {
-                self.registry_c = self.registry_a >> self.combo(operand);
-            },
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::NotLooping => write!(f, "the program doesn't end with `jnz 0`"),
+            ShapeError::ExtraJumps(count) => write!(f, "the program has {} jumps besides the final `jnz 0`", count),
+            ShapeError::Shift(shifts) => write!(
+                f,
+                "the program should shift A with a single `adv 3`, found [{}]",
+                shifts.iter().join(", "),
+            ),
+            ShapeError::Outputs(count) => write!(f, "the program should output once per iteration, found {} `out`", count),
+            ShapeError::TooLong(length) => write!(f, "a program of {} values can't be output by a {} bits register", length, Value::BITS),
+            ShapeError::NoSolution => write!(f, "no value of register A makes the program output itself"),
         }
+    }
+}
 
-        (next_pointer, output)
+fn check_shape(program: &[Instruction]) -> Result<(), ShapeError> {
+    if program.last() != Some(&Instruction::Jnz(0)) {
+        return Err(ShapeError::NotLooping);
     }
 
-    fn combo(&self, operand: u8) -> Value {
-        match operand {
-            0 => 0,
-            1 => 1,
-            2 => 2,
-            3 => 3,
-            4 => self.registry_a,
-            5 => self.registry_b,
-            6 => self.registry_c,
-            7 => panic!("Reserved"),
-            _ => panic!("Invalid operand"),
-        }
+    let jumps = program.iter().filter(|instruction| matches!(instruction, Instruction::Jnz(_))).count();
+    if jumps > 1 {
+        return Err(ShapeError::ExtraJumps(jumps - 1));
     }
-}
 
-impl From<&Input> for Device {
-    fn from(value: &Input) -> Self {
-        let program = value.program.iter().tuples().map(|(instruction, operand)| match instruction {
-            0 => Instruction::Adv(*operand),
-            1 => Instruction::Bxl(*operand as Value),
-            2 => Instruction::Bst(*operand),
-            3 => Instruction::Jnz((*operand / 2) as usize),
-            4 => Instruction::Bxc,
-            5 => Instruction::Out(*operand),
-            6 => Instruction::Bdv(*operand),
-            7 => Instruction::Cdv(*operand),
-            _ => panic!("Invalid instruction"),
-        }).collect();
-
-        Self {
-            registry_a: value.registry_a,
-            registry_b: value.registry_b,
-            registry_c: value.registry_c,
-
-            pointer: 0,
-
-            program,
-        }
+    let shifts = program.iter().filter(|instruction| matches!(instruction, Instruction::Adv(_))).cloned().collect_vec();
+    if shifts != [Instruction::Adv(3)] {
+        return Err(ShapeError::Shift(shifts));
     }
-}
 
-impl Iterator for Device {
-    type Item = Value;
+    let outputs = program.iter().filter(|instruction| matches!(instruction, Instruction::Out(_))).count();
+    if outputs != 1 {
+        return Err(ShapeError::Outputs(outputs));
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            if (self.pointer as usize) >= self.program.len() {
-                return None;
-            }
+    Ok(())
+}
 
-            let (next_pointer, output) = self.execute_instruction(self.pointer);
+/// Smallest value of register A making the program output itself, for programs consuming three bits
+/// of A per iteration. Octal digits of A are found from the most significant one, each new digit
+/// having to produce one more of the trailing values of the program.
+fn solve_part2(input: &Input) -> Result<Value, ShapeError> {
+    check_shape(&Device::from(input).program)?;
 
-            self.pointer = next_pointer;
+    let program = input.program.iter().map(|i| *i as Value).collect::<Vec<_>>();
 
-            if let Some(output) = output {
-                return Some(output);
-            }
-        }
+    if program.len() > (Value::BITS / 3) as usize {
+        return Err(ShapeError::TooLong(program.len()));
     }
-}
-
-fn solve_part1(input: &Input) -> Vec<Value> {
-    Device::from(input).collect()
-}
-
-fn solve_part2(input: &Input) -> Value {
-    let program = input.program.iter().map(|i| *i as u64).collect::<Vec<_>>();
 
     let mut possible_results = vec![0];
 
     for i in (0..program.len()).rev() {
-        let mut next_possible_results = Vec::new();
-
-        for possible_result in possible_results {
-            for j in 0..8 {
-                let tentative_result = possible_result | (j << (3 * i));
-
+        possible_results = possible_results.into_iter()
+            .flat_map(|possible_result| (0..8).map(move |j| (possible_result << 3) | j))
+            .filter(|&tentative_result| {
                 let mut device = Device::from(input);
 
-                device.registry_a = tentative_result >> (3 * i);
+                device.registry_a = tentative_result;
 
-                if let Some(output) = device.next() {
-                    if output == program[i] {
-                        next_possible_results.push(tentative_result);
-                    }
-                }
-            }
-        }
-
-        possible_results = next_possible_results;
+                device.eq(program[i..].iter().copied())
+            })
+            .collect();
     }
 
-    possible_results[0]
+    possible_results.into_iter().min().ok_or(ShapeError::NoSolution)
 }
 
+/// Steps printed by `--trace` at most, the program looping forever when `jnz` never sees A reach 0
+const TRACE_LIMIT: usize = 100_000;
+
 fn main() {
     let input = parse(&read(17).unwrap()).unwrap();
 
+    match env::args().nth(1).as_deref() {
+        Some("--disassemble") => {
+            disassemble(&Device::from(&input)).iter().for_each(|line| println!("{}", line));
+            return;
+        },
+        Some("--trace") => {
+            let steps = trace(&Device::from(&input), TRACE_LIMIT);
+
+            steps.iter().for_each(|step| println!("{}", step));
+            if steps.len() == TRACE_LIMIT {
+                println!("Stopped after {} steps", TRACE_LIMIT);
+            }
+            return;
+        },
+        Some("--outputs") => {
+            for (value, output) in iteration_outputs(&Device::from(&input), 0..1024) {
+                println!("{:>4} {:04o}: {}", value, value, output.map_or("-".to_owned(), |o| o.to_string()));
+            }
+            return;
+        },
+        _ => {},
+    }
+
     println!("{}", solve_part1(&input).into_iter().map(|o| o.to_string()).join(","));
-    println!("{}", solve_part2(&input));
+    match solve_part2(&input) {
+        Ok(value) => println!("{}", value),
+        Err(error) => println!("Unsupported program: {}", error),
+    }
 }
 
 #[cfg(test)]
@@ -233,6 +191,31 @@ Program: 0,1,5,4,3,0
         assert_eq!(parse::<Input>(INPUT), Ok(parsed_input()));
     }
 
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(disassemble(&Device::from(&parsed_input())), vec!["  0: adv 1", "  2: out a", "  4: jnz 0"]);
+        assert_eq!(disassemble(&Device::new(0, 0, 0, &[2, 4, 1, 5, 7, 5, 4, 3])), vec!["  0: bst a", "  2: bxl 5", "  4: cdv b", "  6: bxc"]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let steps = trace(&Device::from(&parsed_input()), TRACE_LIMIT);
+
+        assert_eq!(steps.len(), 30);
+        assert_eq!(trace(&Device::from(&parsed_input()), 10).len(), 10);
+        assert_eq!(trace(&Device::new(1, 0, 0, &[3, 0]), 100).len(), 100);
+        assert_eq!(steps[0].to_string(), "  0: adv 1  a=364 b=0 c=0");
+        assert_eq!(steps[1].to_string(), "  2: out a  a=364 b=0 c=0 out=4");
+        assert_eq!(steps.iter().filter_map(|step| step.output).collect::<Vec<_>>(), solve_part1(&parsed_input()));
+    }
+
+    #[test]
+    fn test_iteration_outputs() {
+        let outputs = iteration_outputs(&Device::new(2024, 0, 0, &[0, 3, 5, 4, 3, 0]), 0..64);
+
+        assert!(outputs.iter().all(|&(value, output)| output == Some((value >> 3) % 8)));
+    }
+
     #[test]
     fn test_solve_part1() {
         assert_eq!(solve_part1(&parsed_input()), vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
@@ -241,6 +224,17 @@ Program: 0,1,5,4,3,0
 
     #[test]
     fn test_solve_part2() {
-        assert_eq!(solve_part2(&Input::new(2024, 0, 0, vec![0, 3, 5, 4, 3, 0])), 117440);
+        assert_eq!(solve_part2(&Input::new(2024, 0, 0, vec![0, 3, 5, 4, 3, 0])), Ok(117440));
+    }
+
+    #[test]
+    fn test_solve_part2_shape() {
+        assert_eq!(solve_part2(&parsed_input()), Err(ShapeError::Shift(vec![Instruction::Adv(1)])));
+        assert_eq!(solve_part2(&Input::new(0, 0, 0, vec![0, 3, 5, 4])), Err(ShapeError::NotLooping));
+        assert_eq!(solve_part2(&Input::new(0, 0, 0, vec![0, 3, 5, 4, 5, 4, 3, 0])), Err(ShapeError::Outputs(2)));
+        assert_eq!(solve_part2(&Input::new(0, 0, 0, vec![0, 3, 5, 0, 3, 0])), Err(ShapeError::NoSolution));
+
+        let long = [vec![0, 3, 5, 4], [1, 0].repeat(8), vec![3, 0]].concat();
+        assert_eq!(solve_part2(&Input::new(0, 0, 0, long)), Err(ShapeError::TooLong(22)));
     }
 }
@@ -1,24 +1,32 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use advent_of_code_2024::{parser::*, read};
 use itertools::Itertools;
-use nom::{bytes::complete::tag, character::complete::u32, multi::many1, sequence::terminated, IResult};
+use nom::{bytes::complete::tag, character::complete::digit1, combinator::recognize, multi::many1, sequence::terminated, IResult};
+
+const NUMERIC_LAYOUT: &str = "789\n456\n123\n 0A";
+const DIRECTIONAL_LAYOUT: &str = " ^A\n<v>";
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 struct Code {
-    numeric_part: u32,
+    keys: String,
 }
 
 impl Code {
-    fn new(numeric_part: u32) -> Self {
-        Self { numeric_part }
+    fn new(keys: &str) -> Self {
+        Self { keys: keys.to_owned() }
+    }
+
+    fn numeric_part(&self) -> u64 {
+        self.keys.trim_end_matches('A').parse().unwrap()
     }
 }
 
 impl Parsable for Code {
     fn parser(input: &str) -> IResult<&str, Self> {
-        let (input, numeric_part) = terminated(u32, tag("A"))(input)?;
+        let (input, keys) = recognize(terminated(digit1, tag("A")))(input)?;
 
-        Ok((input, Self::new(numeric_part)))
+        Ok((input, Self::new(keys)))
     }
 }
 
@@ -41,271 +49,238 @@ impl Parsable for Input {
     }
 }
 
-fn range(a: u8, b: u8) -> Vec<u8> {
-    if a < b {
-        return (a..=b).collect()
-    }
+type Position = (i32, i32);
 
-    if a > b {
-        return (b..=a).rev().collect()
-    }
+const MOVES: [(char, Position); 4] = [('^', (0, -1)), ('v', (0, 1)), ('<', (-1, 0)), ('>', (1, 0))];
 
-    Vec::new()
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum KeypadError {
+    /// A keypad lacks a button that has to be pressed
+    MissingButton(char),
+    DuplicateButton(char),
+    /// The button can't be reached from `A` without leaving the keypad
+    Unreachable(char),
 }
 
-type KeyPadButton = (u8, u8);
-
-trait KeyPad {
-    fn get_start(&self) -> KeyPadButton;
-
-    fn is_valid(&self, position: &KeyPadButton) -> bool;
+impl fmt::Display for KeypadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeypadError::MissingButton(button) => write!(f, "no {} button on the keypad", button),
+            KeypadError::DuplicateButton(button) => write!(f, "several {} buttons on the keypad", button),
+            KeypadError::Unreachable(button) => write!(f, "the {} button can't be reached from A", button),
+        }
+    }
+}
 
-    fn get_paths(&self, from: KeyPadButton, to: KeyPadButton) -> Vec<Vec<KeyPadButton>> {
-        let mut first = vec![from];
+/// Keypad described by an ASCII drawing, one character per button and spaces for gaps
+#[derive(Clone, Debug)]
+struct Keypad {
+    buttons: HashMap<char, Position>,
+    positions: HashMap<Position, char>,
+}
 
-        {
-            for i in range(from.0, to.0).into_iter().skip(1) {
-                first.push((i, from.1));
-            }
+impl Keypad {
+    /// Keypad of the drawing, which must have a single `A` button from which every other one can
+    /// be reached
+    fn from_layout(layout: &str) -> Result<Self, KeypadError> {
+        let positions: HashMap<Position, char> = layout.lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars().enumerate().filter(|(_, c)| *c != ' ').map(move |(x, c)| ((x as i32, y as i32), c))
+            })
+            .collect();
 
-            for j in range(from.1, to.1).into_iter().skip(1) {
-                first.push((to.0, j));
+        let mut buttons = HashMap::new();
+        for (&position, &button) in &positions {
+            if buttons.insert(button, position).is_some() {
+                return Err(KeypadError::DuplicateButton(button));
             }
         }
 
-        let mut second = vec![from];
+        let keypad = Self { buttons, positions };
 
-        {
-            for j in range(from.1, to.1).into_iter().skip(1) {
-                second.push((from.0, j));
-            }
+        let start = *keypad.buttons.get(&'A').ok_or(KeypadError::MissingButton('A'))?;
+        let mut reached = vec![start];
+        let mut queue = VecDeque::from([start]);
 
-            for i in range(from.0, to.0).into_iter().skip(1) {
-                second.push((i, to.1));
+        while let Some(position) = queue.pop_front() {
+            for (_, next) in keypad.neighbours(position) {
+                if !reached.contains(&next) {
+                    reached.push(next);
+                    queue.push_back(next);
+                }
             }
         }
 
-        // Don't ask why, it just works....
-        // The idea is that depending of the direction the optimal path is not the same,
-        // but to find which one is the best for which direction I just did some trial.
-        let paths = if from.0 > to.0 {
-            vec![first, second]
-        } else {
-            vec![second, first]
-        };
+        match keypad.positions.iter().find(|(position, _)| !reached.contains(position)) {
+            Some((_, &button)) => Err(KeypadError::Unreachable(button)),
+            None => Ok(keypad),
+        }
+    }
 
-        paths.into_iter().filter(|path| path.iter().all(|position| self.is_valid(position))).unique().collect()
+    fn neighbours(&self, position: Position) -> impl Iterator<Item = (char, Position)> + '_ {
+        MOVES.iter()
+            .map(move |&(direction, (dx, dy))| (direction, (position.0 + dx, position.1 + dy)))
+            .filter(|(_, next)| self.positions.contains_key(next))
     }
 
-    fn get_possible_buttons_for_code(&self, code: u32) -> Vec<Vec<KeyPadButton>>;
-}
+    /// Every shortest sequence of moves going from one button to the other without leaving the keypad
+    fn paths(&self, from: char, to: char) -> Result<Vec<String>, KeypadError> {
+        let start = *self.buttons.get(&from).ok_or(KeypadError::MissingButton(from))?;
+        let end = *self.buttons.get(&to).ok_or(KeypadError::MissingButton(to))?;
 
-// +---+---+---+
-// | 7 | 8 | 9 |
-// +---+---+---+
-// | 4 | 5 | 6 |
-// +---+---+---+
-// | 1 | 2 | 3 |
-// +---+---+---+
-//     | 0 | A |
-//     +---+---+
-struct NumericKeyPad {
-}
+        let mut distances = HashMap::from([(end, 0)]);
+        let mut queue = VecDeque::from([end]);
 
-impl NumericKeyPad {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
+        while let Some(position) = queue.pop_front() {
+            for (_, next) in self.neighbours(position) {
+                if !distances.contains_key(&next) {
+                    distances.insert(next, distances[&position] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
 
-impl KeyPad for NumericKeyPad {
-    fn get_start(&self) -> KeyPadButton {
-        (2, 0)
-    }
+        let mut paths = Vec::new();
+        let mut stack = vec![(start, String::new())];
 
-    fn is_valid(&self, position: &KeyPadButton) -> bool {
-        position != &(0, 0)
-    }
+        while let Some((position, path)) = stack.pop() {
+            if position == end {
+                paths.push(path);
+                continue;
+            }
 
-    fn get_possible_buttons_for_code(&self, code: u32) -> Vec<Vec<KeyPadButton>> {
-        let mut buttons = Vec::new();
-
-        for number in [code / 100, (code / 10) % 10, code % 10] {
-            buttons.push(match number {
-                0 => (1, 0),
-                1 => (0, 1),
-                2 => (1, 1),
-                3 => (2, 1),
-                4 => (0, 2),
-                5 => (1, 2),
-                6 => (2, 2),
-                7 => (0, 3),
-                8 => (1, 3),
-                9 => (2, 3),
-                _ => panic!("Invalid button"),
-            })
+            for (direction, next) in self.neighbours(position) {
+                if distances.get(&next) == Some(&(distances[&position] - 1)) {
+                    stack.push((next, format!("{}{}", path, direction)));
+                }
+            }
         }
 
-        buttons.push((2, 0));
-
-        vec![buttons]
+        Ok(paths)
     }
 }
 
-//     +---+---+
-//     | ^ | A |
-// +---+---+---+
-// | < | v | > |
-// +---+---+---+
-struct RobotKeyPad {
-    target: Box<dyn KeyPad>,
+/// A door keypad operated through a chain of directional keypads, the last one being pressed
+/// by hand. Every robot arm starts on the `A` button.
+struct KeypadChain {
+    keypads: Vec<Keypad>,
+    cache: HashMap<(usize, char, char), (u64, String)>,
 }
 
-impl RobotKeyPad {
-    pub fn new(target: Box<dyn KeyPad>) -> Self {
-        Self { target }
-    }
-
-    pub fn get_paths_for_path(&self, path: Vec<KeyPadButton>) -> Vec<Vec<KeyPadButton>> {
-        let mut current = vec![vec![]];
-
-        for (start, end) in [self.target.get_start()].into_iter().chain(path.into_iter()).tuple_windows() {
-            let mut paths_buttons = Vec::new();
+impl KeypadChain {
+    /// Chain of `depth` directional keypads, which must have the four direction buttons
+    fn new(door: Keypad, directional: Keypad, depth: usize) -> Result<Self, KeypadError> {
+        if let Some(button) = MOVES.iter().map(|(direction, _)| *direction).find(|direction| !directional.buttons.contains_key(direction)) {
+            return Err(KeypadError::MissingButton(button));
+        }
 
-            for path in self.target.get_paths(start, end) {
-                let mut path_buttons = Vec::new();
+        let mut keypads = vec![door];
+        keypads.extend(std::iter::repeat_n(directional, depth));
 
-                for (a, b) in path.into_iter().tuple_windows() {
-                    path_buttons.push(match ((b.0 as i32 - a.0 as i32), (b.1 as i32 - a.1 as i32)) {
-                        (-1, 0) => (0, 0),
-                        (1, 0) => (2, 0),
-                        (0, -1) => (1, 0),
-                        (0, 1) => (1, 1),
-                        _ => panic!("Invalid movment"),
-                    });
-                }
-
-                path_buttons.push((2, 1));
+        Ok(Self { keypads, cache: HashMap::new() })
+    }
 
-                paths_buttons.push(path_buttons);
-            }
+    /// Minimal number of hand presses needed to move the arm of keypad `level` from `from` to
+    /// `to` and press it, along with the keys to press on the next keypad to do so
+    fn press(&mut self, level: usize, from: char, to: char) -> Result<(u64, String), KeypadError> {
+        if level + 1 == self.keypads.len() {
+            return Ok((1, to.to_string()));
+        }
 
-            current = current.into_iter().cartesian_product(paths_buttons.into_iter()).map(|(a, b)| [a, b].concat()).collect();
+        if let Some(cached) = self.cache.get(&(level, from, to)) {
+            return Ok(cached.clone());
         }
 
-        current
-    }
-}
+        let best = self.keypads[level].paths(from, to)?
+            .into_iter()
+            .map(|path| {
+                let keys = path + "A";
+                Ok((self.cost(level + 1, &keys)?, keys))
+            })
+            .collect::<Result<Vec<_>, KeypadError>>()?
+            .into_iter()
+            .min()
+            // Keypads are connected, there's always a path between two of their buttons
+            .unwrap();
 
-impl KeyPad for RobotKeyPad {
-    fn get_start(&self) -> KeyPadButton {
-        (2, 1)
-    }
+        self.cache.insert((level, from, to), best.clone());
 
-    fn is_valid(&self, position: &KeyPadButton) -> bool {
-        position != &(0, 1)
+        Ok(best)
     }
 
-    fn get_possible_buttons_for_code(&self, code: u32) -> Vec<Vec<KeyPadButton>> {
-        let mut possible_buttons = Vec::new();
+    /// Minimal number of hand presses needed to type `keys` on keypad `level`
+    fn cost(&mut self, level: usize, keys: &str) -> Result<u64, KeypadError> {
+        ['A'].into_iter().chain(keys.chars()).tuple_windows().map(|(from, to)| Ok(self.press(level, from, to)?.0)).sum()
+    }
 
-        for buttons in self.target.get_possible_buttons_for_code(code) {
-            possible_buttons = [
-                possible_buttons,
-                self.get_paths_for_path(buttons),
-            ].concat();
+    fn expand(&mut self, level: usize, keys: &str) -> Result<String, KeypadError> {
+        if level + 1 == self.keypads.len() {
+            return Ok(keys.to_owned());
         }
 
-        let best = possible_buttons.iter().map(|puttons| puttons.len()).min().unwrap();
+        let next = ['A'].into_iter()
+            .chain(keys.chars())
+            .tuple_windows()
+            .map(|(from, to)| Ok(self.press(level, from, to)?.1))
+            .collect::<Result<String, KeypadError>>()?;
 
-        possible_buttons.into_iter()
-            .filter(|buttons| buttons.len() == best)
-            .collect()
+        self.expand(level + 1, &next)
     }
-}
 
-fn group(buttons: Vec<KeyPadButton>) -> HashMap<Vec<KeyPadButton>, usize> {
-    let mut groups = Vec::new();
-    let mut current = Vec::new();
-
-    for button in buttons {
-        current.push(button);
-
-        if button == (2, 1) {
-            groups.push(current);
-            current = Vec::new();
-        }
+    /// Minimal number of hand presses to type `code` on the door keypad
+    fn presses(&mut self, code: &str) -> Result<u64, KeypadError> {
+        self.cost(0, code)
     }
 
-    groups.into_iter().counts()
+    /// One of the shortest hand press sequences typing `code`, its length grows exponentially
+    /// with the depth of the chain
+    fn sequence(&mut self, code: &str) -> Result<String, KeypadError> {
+        self.expand(0, code)
+    }
 }
 
-fn len_from_group(groups: &HashMap<Vec<KeyPadButton>, usize>) -> usize {
-    groups.iter().map(|(group, count)| group.len() * count).sum()
+/// Chain of the puzzle, from the numeric keypad of the door through `depth` directional keypads
+fn door_chain(depth: usize) -> Result<KeypadChain, KeypadError> {
+    KeypadChain::new(Keypad::from_layout(NUMERIC_LAYOUT)?, Keypad::from_layout(DIRECTIONAL_LAYOUT)?, depth)
 }
 
-fn solve_v3(input: &Input, robots_count: u8) -> usize {
-    let start_keypad = RobotKeyPad::new(Box::new(NumericKeyPad::new()));
-    let loop_keypad = RobotKeyPad::new(Box::new(RobotKeyPad::new(Box::new(NumericKeyPad::new()))));
-
-    let mut result = 0;
-
-    for code in &input.codes {
-        let mut best = None;
-
-        for path in start_keypad.get_possible_buttons_for_code(code.numeric_part) {
-            let mut counts = group(path);
-
-            for _ in 1..robots_count {
-                let mut new_counts = HashMap::new();
+fn complexity(input: &Input, robots_count: usize) -> Result<u64, KeypadError> {
+    let mut chain = door_chain(robots_count)?;
 
-                for (pattern, count) in counts {
-                    let expandeds = loop_keypad.get_paths_for_path(pattern);
-
-                    // Keep only best patterns
-                    let best = expandeds.iter().map(|puttons| puttons.len()).min().unwrap();
-                    let expandeds = expandeds.into_iter().filter(|buttons| buttons.len() == best).collect::<Vec<_>>();
+    input.codes.iter().map(|code| Ok(chain.presses(&code.keys)? * code.numeric_part())).sum()
+}
 
-                    let groups = group(expandeds[0].clone());
+fn solve_part1(input: &Input) -> Result<u64, KeypadError> {
+    complexity(input, 3)
+}
 
-                    for (group, group_count) in groups {
-                        *new_counts.entry(group).or_default() += count * group_count;
-                    }
-                }
+fn solve_part2(input: &Input) -> Result<u64, KeypadError> {
+    complexity(input, 26)
+}
 
-                counts = new_counts
-            }
+fn main() {
+    let input: Input = parse(&read(21).unwrap()).unwrap();
 
-            let path_len: usize = len_from_group(&counts);
+    if std::env::args().nth(1).as_deref() == Some("--print") {
+        let sequences = door_chain(3).and_then(|mut chain| {
+            input.codes.iter().map(|code| Ok((&code.keys, chain.sequence(&code.keys)?))).collect::<Result<Vec<_>, _>>()
+        });
 
-            if let Some(len) = best {
-                if path_len < len {
-                    best = Some(path_len);
-                }
-            } else {
-                best = Some(path_len);
-            }
+        match sequences {
+            Ok(sequences) => sequences.iter().for_each(|(keys, sequence)| println!("{}: {}", keys, sequence)),
+            Err(error) => println!("Invalid keypads: {}", error),
         }
 
-        result += best.unwrap() * (code.numeric_part as usize);
+        return;
     }
 
-    result
-}
-
-fn solve_part1(input: &Input) -> usize {
-    solve_v3(input, 3)
-}
-
-fn solve_part2(input: &Input) -> usize {
-    solve_v3(input, 26)
-}
-
-fn main() {
-    let input = parse(&read(21).unwrap()).unwrap();
-
-    println!("{}", solve_part1(&input));
-    println!("{}", solve_part2(&input));
+    for solution in [solve_part1(&input), solve_part2(&input)] {
+        match solution {
+            Ok(complexity) => println!("{}", complexity),
+            Err(error) => println!("Invalid keypads: {}", error),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -321,83 +296,101 @@ mod tests {
 
     fn parsed_input() -> Input {
         Input::new(vec![
-            Code::new(029),
-            Code::new(980),
-            Code::new(179),
-            Code::new(456),
-            Code::new(379),
+            Code::new("029A"),
+            Code::new("980A"),
+            Code::new("179A"),
+            Code::new("456A"),
+            Code::new("379A"),
         ])
     }
 
+    /// Types `presses` by hand and returns what comes out of the door keypad
+    fn replay(chain: &KeypadChain, presses: &str) -> String {
+        chain.keypads.iter().rev().skip(1).fold(presses.to_owned(), |presses, keypad| {
+            let mut position = keypad.buttons[&'A'];
+            let mut output = String::new();
+
+            for key in presses.chars() {
+                match MOVES.iter().find(|(direction, _)| *direction == key) {
+                    Some((_, (dx, dy))) => {
+                        position = (position.0 + dx, position.1 + dy);
+                        assert!(keypad.positions.contains_key(&position), "arm over a gap");
+                    },
+                    None => output.push(keypad.positions[&position]),
+                }
+            }
+
+            output
+        })
+    }
+
     #[test]
     fn test_parser() {
         assert_eq!(parse::<Input>(INPUT), Ok(parsed_input()));
+        assert_eq!(parsed_input().codes[0].numeric_part(), 29);
     }
 
     #[test]
-    fn test_get_paths() {
-        let numeric = NumericKeyPad::new();
-        let robot = RobotKeyPad::new(Box::new(NumericKeyPad::new()));
-
-        assert_eq!(numeric.get_paths((2, 0), (1, 1)), vec![
-            vec![(2, 0), (1, 0), (1, 1)],
-            vec![(2, 0), (2, 1), (1, 1)],
-        ]);
-
-        assert_eq!(robot.get_paths((2, 0), (1, 1)), vec![
-            vec![(2, 0), (1, 0), (1, 1)],
-            vec![(2, 0), (2, 1), (1, 1)],
-        ]);
+    fn test_paths() {
+        let numeric = Keypad::from_layout(NUMERIC_LAYOUT).unwrap();
+        let paths = |from, to| numeric.paths(from, to).unwrap().into_iter().sorted().collect::<Vec<_>>();
+
+        assert_eq!(paths('A', '2'), vec!["<^", "^<"]);
+        assert_eq!(paths('A', 'A'), vec![""]);
+        assert_eq!(paths('A', '9'), vec!["^^^"]);
+        assert_eq!(paths('A', '1'), vec!["<^<", "^<<"]);
+        assert_eq!(paths('7', '0'), vec![">vvv", "v>vv", "vv>v"]);
+        assert_eq!(numeric.paths('A', 'B'), Err(KeypadError::MissingButton('B')));
     }
 
     #[test]
-    fn test_get_paths_same() {
-        let numeric = NumericKeyPad::new();
-        let robot = RobotKeyPad::new(Box::new(NumericKeyPad::new()));
+    fn test_sequence() {
+        let mut chain = door_chain(3).unwrap();
 
-        assert_eq!(numeric.get_paths((2, 0), (2, 0)), vec![
-            vec![(2, 0)],
-        ]);
+        for code in parsed_input().codes {
+            let sequence = chain.sequence(&code.keys).unwrap();
 
-        assert_eq!(robot.get_paths((2, 0), (2, 0)), vec![
-            vec![(2, 0)],
-        ]);
+            assert_eq!(Ok(sequence.len() as u64), chain.presses(&code.keys));
+            assert_eq!(replay(&chain, &sequence), code.keys);
+        }
+
+        assert_eq!(chain.presses("029A"), Ok(68));
     }
 
     #[test]
-    fn test_get_paths_line() {
-        let numeric = NumericKeyPad::new();
-        let robot = RobotKeyPad::new(Box::new(NumericKeyPad::new()));
-
-        assert_eq!(numeric.get_paths((2, 0), (2, 2)), vec![
-            vec![(2, 0), (2, 1), (2, 2)],
-        ]);
-
-        assert_eq!(robot.get_paths((2, 0), (2, 2)), vec![
-            vec![(2, 0), (2, 1), (2, 2)],
-        ]);
+    fn test_custom_layout() {
+        let mut chain = KeypadChain::new(
+            Keypad::from_layout("1 2\n345\nA 6").unwrap(),
+            Keypad::from_layout(DIRECTIONAL_LAYOUT).unwrap(),
+            2,
+        ).unwrap();
+
+        let sequence = chain.sequence("26A").unwrap();
+
+        assert_eq!(replay(&chain, &sequence), "26A");
+        assert_eq!(Ok(sequence.len() as u64), chain.presses("26A"));
+        assert_eq!(chain.presses("29A"), Err(KeypadError::MissingButton('9')));
+        assert_eq!(chain.sequence("29A"), Err(KeypadError::MissingButton('9')));
     }
 
     #[test]
-    fn get_possible_buttons_for_code() {
-        let robot = RobotKeyPad::new(Box::new(NumericKeyPad::new()));
-
-        let mut left = robot.get_possible_buttons_for_code(029);
-        let mut right = vec![
-            vec![(0, 0), (2, 1), (1, 1), (2, 1), (2, 0), (1, 1), (1, 1), (2, 1), (1, 0), (1, 0), (1, 0), (2, 1)],
-            // This one is given in the example but since it's not an optimal one we don't return it
-            // vec![(0, 0), (2, 1), (1, 1), (2, 1), (1, 1), (2, 0), (1, 1), (2, 1), (1, 0), (1, 0), (1, 0), (2, 1)],
-            vec![(0, 0), (2, 1), (1, 1), (2, 1), (1, 1), (1, 1), (2, 0), (2, 1), (1, 0), (1, 0), (1, 0), (2, 1)],
-        ];
+    fn test_invalid_layouts() {
+        assert_eq!(Keypad::from_layout("123\n456").unwrap_err(), KeypadError::MissingButton('A'));
+        assert_eq!(Keypad::from_layout("1A\n11").unwrap_err(), KeypadError::DuplicateButton('1'));
+        assert_eq!(Keypad::from_layout("1A\n  \n 2").unwrap_err(), KeypadError::Unreachable('2'));
 
-        left.sort();
-        right.sort();
+        let numeric = Keypad::from_layout(NUMERIC_LAYOUT).unwrap();
 
-        assert_eq!(left, right);
+        assert_eq!(KeypadChain::new(numeric, Keypad::from_layout("^A\n<v").unwrap(), 2).err(), Some(KeypadError::MissingButton('>')));
     }
 
     #[test]
     fn test_solve_part1() {
-        assert_eq!(solve_part1(&parsed_input()), 126384);
+        assert_eq!(solve_part1(&parsed_input()), Ok(126384));
+    }
+
+    #[test]
+    fn test_solve_part2() {
+        assert_eq!(solve_part2(&parsed_input()), Ok(154115708116294));
     }
 }
@@ -1,6 +1,4 @@
-use std::collections::{BTreeSet, HashSet};
-
-use advent_of_code_2024::{parser::*, read};
+use advent_of_code_2024::{graph::Graph, parser::*, read};
 use itertools::Itertools;
 use nom::{bytes::complete::tag, character::complete::alpha1, combinator::into, multi::many1, sequence::{separated_pair, terminated}, IResult};
 
@@ -38,53 +36,18 @@ impl Parsable for Input {
     }
 }
 
-fn build_three_sets(connections: &[(Id, Id)]) -> HashSet<BTreeSet<&Id>> {
-    let connections_set: HashSet<BTreeSet<&Id>> = connections.into_iter().map(|(a, b)| BTreeSet::from([a, b])).collect();
-    let ids_set = connections_set.iter().flatten().collect::<HashSet<_>>();
-
-    let mut sets = HashSet::new();
-
-    for (a, b) in connections {
-        for c in &ids_set {
-            if connections_set.contains(&BTreeSet::from([b, c])) && connections_set.contains(&BTreeSet::from([a, c])) {
-                sets.insert(BTreeSet::from([a, b, c]));
-            }
-        }
+impl Input {
+    fn graph(&self) -> Graph<Id> {
+        Graph::from_undirected_pairs(self.connections.iter().cloned())
     }
-
-    sets
-}
-
-fn build_full_sets(connections: &[(Id, Id)]) -> HashSet<BTreeSet<&Id>> {
-    let connections_set: HashSet<BTreeSet<&Id>> = connections.into_iter().map(|(a, b)| BTreeSet::from([a, b])).collect();
-    let ids_set = connections_set.iter().flatten().collect::<HashSet<_>>();
-
-    let mut sets: HashSet<BTreeSet<&Id>> = connections_set
-        .iter()
-        .cloned()
-        .collect();
-
-    for &c in ids_set {
-        for set in sets.clone() {
-            if set.iter().all(|id| connections_set.contains(&BTreeSet::from([id, c]))) {
-                let mut set = sets.take(&set).unwrap();
-
-                set.insert(c);
-
-                sets.insert(set);
-            }
-        }
-    }
-
-    sets
 }
 
 fn solve_part1(input: &Input) -> usize {
-    build_three_sets(&input.connections).into_iter().filter(|set| set.iter().any(|id| id.0.starts_with("t"))).count()
+    input.graph().triangles().into_iter().filter(|set| set.iter().any(|id| id.0.starts_with("t"))).count()
 }
 
 fn solve_part2(input: &Input) -> String {
-    build_full_sets(&input.connections).into_iter().max_by(|a, b| a.len().cmp(&b.len())).unwrap().into_iter().map(|id| id.0.clone()).sorted().join(",")
+    input.graph().maximum_clique().into_iter().map(|id| id.0.clone()).join(",")
 }
 
 fn main() {
@@ -96,6 +59,8 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
     use super::*;
 
     const INPUT: &str = "kh-tc
@@ -175,12 +140,28 @@ td-yn
     }
 
     #[test]
-    fn test_build_three_sets() {
-        for set in build_three_sets(&parsed_input().connections) {
-            println!("{:?}", set);
-        }
+    fn test_triangles() {
+        assert_eq!(parsed_input().graph().triangles().len(), 12);
+    }
+
+    #[test]
+    fn test_k_cliques() {
+        let input = parsed_input();
+        let graph = input.graph();
+
+        assert_eq!(graph.k_cliques(4), vec![BTreeSet::from([&Id::from("co"), &Id::from("de"), &Id::from("ka"), &Id::from("ta")])]);
+        assert!(graph.k_cliques(5).is_empty());
+    }
+
+    #[test]
+    fn test_maximal_cliques() {
+        let input = parsed_input();
+        let graph = input.graph();
+
+        let cliques = graph.maximal_cliques();
 
-        assert_eq!(build_three_sets(&parsed_input().connections).len(), 12);
+        assert!(cliques.iter().all(|clique| clique.iter().tuple_combinations().all(|(a, b)| graph.contains_edge(a, b))));
+        assert!(cliques.contains(&BTreeSet::from([&Id::from("co"), &Id::from("de"), &Id::from("ka"), &Id::from("ta")])));
     }
 
     #[test]
@@ -1,6 +1,6 @@
-use std::{collections::HashMap, convert::identity, fmt};
+use std::{collections::HashMap, convert::identity, env, fmt};
 
-use advent_of_code_2024::{parser::*, read};
+use advent_of_code_2024::{graph::Graph, parser::*, read};
 use itertools::Itertools;
 use nom::{branch::alt, bytes::complete::tag, character::complete::{alphanumeric1, u8}, combinator::map, multi::many1, sequence::tuple, IResult};
 
@@ -43,6 +43,15 @@ enum Gate {
     Xor(Id, Id),
 }
 
+impl Gate {
+    fn operands(&self) -> Option<(&Id, &Id)> {
+        match self {
+            Gate::Value(_) => None,
+            Gate::And(left, right) | Gate::Or(left, right) | Gate::Xor(left, right) => Some((left, right)),
+        }
+    }
+}
+
 impl PartialEq<Gate> for Gate {
     fn eq(&self, other: &Gate) -> bool {
         match (self, other) {
@@ -60,7 +69,29 @@ struct Input {
     gates: HashMap<Id, Gate>,
 }
 
-impl Input {
+impl Parsable for Input {
+    fn parser(input: &str) -> IResult<&str, Self> {
+        let (input, gates) = many1(alt((
+            map(tag("\n"), |_| None),
+            map(tuple((Id::parser, tag(": "), u8, tag("\n"))), |(id, _, value, _)| Some((id, Gate::Value(value == 1)))),
+            map(tuple((Id::parser, tag(" AND "), Id::parser, tag(" -> "), Id::parser, tag("\n"))), |(left, _, right, _, id, _)| Some((id, Gate::And(left, right)))),
+            map(tuple((Id::parser, tag(" OR "), Id::parser, tag(" -> "), Id::parser, tag("\n"))), |(left, _, right, _, id, _)| Some((id, Gate::Or(left, right)))),
+            map(tuple((Id::parser, tag(" XOR "), Id::parser, tag(" -> "), Id::parser, tag("\n"))), |(left, _, right, _, id, _)| Some((id, Gate::Xor(left, right)))),
+        )))(input)?;
+
+        Ok((input, Input {
+            gates: gates.into_iter().filter_map(identity).collect(),
+        }))
+    }
+}
+
+/// Wires of a circuit, each one driven either by a constant or by a gate over two other wires
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Netlist {
+    gates: HashMap<Id, Gate>,
+}
+
+impl Netlist {
     fn find(&self, gate: &Gate) -> Option<Id> {
         self.find_by(|v| v == gate)
     }
@@ -71,6 +102,17 @@ impl Input {
             .map(|(id, _)| id.to_owned())
     }
 
+    /// Other input of a gate matching `predicate` and reading `wire`
+    fn other_operand(&self, wire: &Id, predicate: impl Fn(&Gate) -> bool) -> Option<Id> {
+        self.gates.values()
+            .filter(|gate| predicate(gate))
+            .find_map(|gate| match gate.operands() {
+                Some((left, right)) if left == wire => Some(right.to_owned()),
+                Some((left, right)) if right == wire => Some(left.to_owned()),
+                _ => None,
+            })
+    }
+
     fn swap(&mut self, a: &Id, b: &Id) {
         let (a, a_gate) = self.gates.remove_entry(a).unwrap();
         let (b, b_gate) = self.gates.remove_entry(b).unwrap();
@@ -78,209 +120,268 @@ impl Input {
         self.gates.insert(a, b_gate);
         self.gates.insert(b, a_gate);
     }
-}
 
-impl Parsable for Input {
-    fn parser(input: &str) -> IResult<&str, Self> {
-        let (input, gates) = many1(alt((
-            map(tag("\n"), |_| None),
-            map(tuple((Id::parser, tag(": "), u8, tag("\n"))), |(id, _, value, _)| Some((id, Gate::Value(value == 1)))),
-            map(tuple((Id::parser, tag(" AND "), Id::parser, tag(" -> "), Id::parser, tag("\n"))), |(left, _, right, _, id, _)| Some((id, Gate::And(left, right)))),
-            map(tuple((Id::parser, tag(" OR "), Id::parser, tag(" -> "), Id::parser, tag("\n"))), |(left, _, right, _, id, _)| Some((id, Gate::Or(left, right)))),
-            map(tuple((Id::parser, tag(" XOR "), Id::parser, tag(" -> "), Id::parser, tag("\n"))), |(left, _, right, _, id, _)| Some((id, Gate::Xor(left, right)))),
-        )))(input)?;
+    fn graph(&self) -> Graph<Id> {
+        let mut graph = Graph::new();
 
-        Ok((input, Input {
-            gates: gates.into_iter().filter_map(identity).collect(),
-        }))
+        for (id, gate) in &self.gates {
+            graph.add_node(id.to_owned());
+
+            if let Some((left, right)) = gate.operands() {
+                graph.add_edge(left.to_owned(), id.to_owned(), ());
+                graph.add_edge(right.to_owned(), id.to_owned(), ());
+            }
+        }
+
+        graph
     }
-}
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-struct Circuit {
-    gates: HashMap<Id, Gate>,
-    cache: HashMap<Id, bool>,
-}
+    /// Wires in an order where each one comes after its inputs, or a cycle of wires if there is one
+    fn order(&self) -> Result<Vec<Id>, Vec<Id>> {
+        self.graph()
+            .toposort()
+            .map(|order| order.into_iter().cloned().collect())
+            .map_err(|cycle| cycle.into_iter().cloned().collect())
+    }
 
-impl Circuit {
-    fn get(&mut self, id: &Id) -> Option<bool> {
-        if let Some(&value) = self.cache.get(id) {
-            return Some(value);
+    /// Value of every wire, with `inputs` overriding the constant ones
+    fn evaluate(&self, inputs: &HashMap<Id, bool>) -> Result<HashMap<Id, bool>, Vec<Id>> {
+        let mut values: HashMap<Id, bool> = HashMap::new();
+
+        for id in self.order()? {
+            let value = match self.gates.get(&id) {
+                None => inputs.get(&id).copied().unwrap_or(false),
+                Some(Gate::Value(value)) => inputs.get(&id).copied().unwrap_or(*value),
+                Some(Gate::And(left, right)) => values[left] & values[right],
+                Some(Gate::Or(left, right)) => values[left] | values[right],
+                Some(Gate::Xor(left, right)) => values[left] ^ values[right],
+            };
+
+            values.insert(id, value);
         }
 
-        let result = match self.gates.get(id).cloned() {
-            None => {
-                return None;
-            },
-            Some(Gate::Value(value)) => value,
-            Some(Gate::And(left, right)) => self.get(&left).unwrap() & self.get(&right).unwrap(),
-            Some(Gate::Or(left, right)) => self.get(&left).unwrap() | self.get(&right).unwrap(),
-            Some(Gate::Xor(left, right)) => self.get(&left).unwrap() ^ self.get(&right).unwrap(),
-        };
+        Ok(values)
+    }
+
+    /// Wires named after the prefix, least significant bit first
+    fn bus(&self, prefix: char) -> Vec<Id> {
+        self.gates.keys()
+            .filter(|id| id.0.starts_with(prefix) && id.0[1..].chars().all(|c| c.is_ascii_digit()))
+            .cloned()
+            .sorted()
+            .collect()
+    }
+
+    fn width(&self) -> usize {
+        self.bus('x').len()
+    }
+
+    fn read(&self, values: &HashMap<Id, bool>, prefix: char) -> u64 {
+        self.bus(prefix).iter()
+            .enumerate()
+            .filter(|(_, id)| values[id])
+            .map(|(i, _)| 1 << i)
+            .sum()
+    }
+
+    fn output(&self) -> Result<u64, Vec<Id>> {
+        Ok(self.read(&self.evaluate(&HashMap::new())?, 'z'))
+    }
 
-        self.cache.insert(id.to_owned(), result);
+    /// Output of the circuit with the x and y wires set to the bits of the given numbers
+    fn run(&self, x: u64, y: u64) -> Result<u64, Vec<Id>> {
+        let inputs = [('x', x), ('y', y)].into_iter()
+            .flat_map(|(prefix, value)| {
+                self.bus(prefix).into_iter().enumerate().map(move |(i, id)| (id, value >> i & 1 == 1))
+            })
+            .collect();
 
-        return Some(result);
+        Ok(self.read(&self.evaluate(&inputs)?, 'z'))
     }
 
-    fn get_z(&mut self) -> u64 {
-        let mut value = 0;
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph netlist {\n");
+
+        for (id, gate) in self.gates.iter().sorted_by_key(|(id, _)| *id) {
+            match gate {
+                Gate::Value(value) => dot += &format!("    {} [shape=box, label=\"{}: {}\"];\n", id, id, *value as u8),
+                Gate::And(..) => dot += &format!("    {} [label=\"{}\\nAND\"];\n", id, id),
+                Gate::Or(..) => dot += &format!("    {} [label=\"{}\\nOR\"];\n", id, id),
+                Gate::Xor(..) => dot += &format!("    {} [label=\"{}\\nXOR\"];\n", id, id),
+            }
+        }
 
-        for i in 0..100 {
-            if let Some(bit) = self.get(&Id::z(i)) {
-                if bit {
-                    value += 1 << i;
-                }
-            } else {
-                break;
+        for (id, gate) in self.gates.iter().sorted_by_key(|(id, _)| *id) {
+            if let Some((left, right)) = gate.operands() {
+                dot += &format!("    {} -> {};\n    {} -> {};\n", left, id, right, id);
             }
         }
 
-        value
+        dot += "}\n";
+
+        dot
+    }
+
+    /// Checks one bit of a ripple carry adder given the carry from the previous bit, returning the
+    /// carry to the next one
+    fn check_bit(&self, bit: usize, carry: &Id) -> Result<Id, Miswiring> {
+        let sum = self.find(&Gate::Xor(Id::x(bit), Id::y(bit))).ok_or(Miswiring::new(bit, vec![]))?;
+        let and = self.find(&Gate::And(Id::x(bit), Id::y(bit))).ok_or(Miswiring::new(bit, vec![]))?;
+
+        let output = Gate::Xor(sum.clone(), carry.clone());
+
+        if self.gates.get(&Id::z(bit)) != Some(&output) {
+            if let Some(wire) = self.find(&output) {
+                return Err(Miswiring::new(bit, vec![Id::z(bit), wire]));
+            }
+
+            if let Some(wire) = self.other_operand(carry, |gate| matches!(gate, Gate::Xor(..))) {
+                return Err(Miswiring::new(bit, vec![sum, wire]));
+            }
+
+            if let Some(wire) = self.other_operand(&sum, |gate| matches!(gate, Gate::Xor(..))) {
+                return Err(Miswiring::new(bit, vec![carry.clone(), wire]));
+            }
+
+            return Err(Miswiring::new(bit, vec![Id::z(bit)]));
+        }
+
+        let carried = self.find(&Gate::And(sum, carry.clone())).ok_or(Miswiring::new(bit, vec![carry.clone()]))?;
+
+        if let Some(next) = self.find(&Gate::Or(and.clone(), carried.clone())) {
+            return Ok(next);
+        }
+
+        if let Some(wire) = self.other_operand(&carried, |gate| matches!(gate, Gate::Or(..))) {
+            return Err(Miswiring::new(bit, vec![and, wire]));
+        }
+
+        if let Some(wire) = self.other_operand(&and, |gate| matches!(gate, Gate::Or(..))) {
+            return Err(Miswiring::new(bit, vec![carried, wire]));
+        }
+
+        Err(Miswiring::new(bit, vec![and, carried]))
+    }
+
+    /// Checks the circuit is wired as a ripple carry adder of the x and y wires, reporting the first
+    /// miswired bit otherwise
+    fn verify_adder(&self) -> Result<(), Miswiring> {
+        let width = self.width();
+
+        if width == 0 {
+            return Ok(());
+        }
+
+        let sum = Gate::Xor(Id::x(0), Id::y(0));
+
+        if self.gates.get(&Id::z(0)) != Some(&sum) {
+            return Err(Miswiring::new(0, self.find(&sum).into_iter().chain([Id::z(0)]).collect()));
+        }
+
+        let mut carry = self.find(&Gate::And(Id::x(0), Id::y(0))).ok_or(Miswiring::new(0, vec![]))?;
+
+        for bit in 1..width {
+            carry = self.check_bit(bit, &carry)?;
+        }
+
+        if carry != Id::z(width) {
+            return Err(Miswiring::new(width, vec![Id::z(width), carry]));
+        }
+
+        Ok(())
+    }
+
+    /// Swaps pairs of wires until the circuit is a ripple carry adder, returning each miswiring
+    /// fixed along the way, or the first one that can't be fixed by a swap
+    fn repair_adder(&self) -> Result<Vec<Miswiring>, Miswiring> {
+        let mut netlist = self.clone();
+        let mut fixed = Vec::new();
+
+        while let Err(miswiring) = netlist.verify_adder() {
+            match miswiring.wires.as_slice() {
+                [a, b] if a != b && netlist.gates.contains_key(a) && netlist.gates.contains_key(b) && fixed.len() < self.gates.len() => {
+                    netlist.swap(a, b);
+                    fixed.push(miswiring);
+                },
+                _ => return Err(miswiring),
+            }
+        }
+
+        Ok(fixed)
     }
 }
 
-impl From<&Input> for Circuit {
+impl From<&Input> for Netlist {
     fn from(value: &Input) -> Self {
-        Self { gates: value.gates.to_owned(), cache: HashMap::new() }
+        Self { gates: value.gates.to_owned() }
     }
 }
 
-fn solve_part1(input: &Input) -> u64 {
-    Circuit::from(input).get_z()
+/// Bit of an adder whose wiring doesn't match a ripple carry adder, along with the wires to swap
+/// to fix it when they could be found
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Miswiring {
+    bit: usize,
+    wires: Vec<Id>,
 }
 
-fn solve_part2(input: &Input) -> String {
-    let mut input = input.clone();
-    let mut swaps = Vec::new();
-
-    'check: loop {
-        // rem(O) is a special case
-        let mut prev_rem = input
-            .find(&Gate::And(Id::x(0), Id::y(0)))
-            .expect("rem(0) not found");
-
-        for i in 1..=44 {
-            let xor = input
-                .find(&Gate::Xor(Id::x(i), Id::y(i)))
-                .expect(&format!("xor({}) not found", i));
-
-            let and = input
-                .find(&Gate::And(Id::x(i), Id::y(i)))
-                .expect(&format!("and({}) not found", i));
-
-            // Check that z(i) has the right opperands
-            if let Some(Gate::Xor(a, b)) = input.gates.get(&Id::z(i)).cloned() {
-                if a == xor && b != prev_rem {
-                    input.swap(&b, &prev_rem);
-                    swaps.push((b, prev_rem));
-
-                    // Restart check with fixed input
-                    continue 'check;
-                }
-
-                if a != xor && b == prev_rem {
-                    input.swap(&a, &xor);
-                    swaps.push((a, xor));
-
-                    // Restart check with fixed input
-                    continue 'check;
-                }
-
-                if b == xor && a != prev_rem {
-                    input.swap(&a, &prev_rem);
-                    swaps.push((a, prev_rem));
-
-                    // Restart check with fixed input
-                    continue 'check;
-                }
-
-                if b != xor && a == prev_rem {
-                    input.swap(&b, &xor);
-                    swaps.push((b, xor));
-
-                    // Restart check with fixed input
-                    continue 'check;
-                }
-            }
+impl Miswiring {
+    fn new(bit: usize, wires: Vec<Id>) -> Self {
+        Self { bit, wires }
+    }
+}
 
-            // Rem
-            let xor_and_prev_rem = input
-                .find(&Gate::And(xor, prev_rem))
-                .expect(&format!("xor_and_prev_rem{} not found", i));
-
-            let rem = input.find(&Gate::Or(and.clone(), xor_and_prev_rem.clone()));
-
-            if rem.is_none() {
-                // We didn't find rem(i), this means either and(i) or xor_and_prev_rem(i) need to be swapped
-                if let Some(swap) = input
-                    .gates
-                    .values()
-                    .find_map(|gate| match gate {
-                        Gate::Or(a, b) => {
-                            if a == &and {
-                                return Some(b.clone());
-                            }
-
-                            if b == &and {
-                                return Some(a.clone());
-                            }
-
-                            return None;
-                        },
-                        _ => None,
-                    }) {
-                    input.swap(&xor_and_prev_rem, &swap);
-                    swaps.push((xor_and_prev_rem, swap));
-
-                    // Restart check with fixed input
-                    continue 'check;
-                }
-
-                if let Some(swap) = input
-                    .gates
-                    .values()
-                    .find_map(|gate| match gate {
-                        Gate::Or(a, b) => {
-                            if a == &xor_and_prev_rem {
-                                return Some(b.clone());
-                            }
-
-                            if b == &xor_and_prev_rem {
-                                return Some(a.clone());
-                            }
-
-                            return None;
-                        },
-                        _ => None,
-                    }) {
-                    input.swap(&and, &swap);
-                    swaps.push((and, swap));
-
-                    // Restart check with fixed input
-                    continue 'check;
-                }
-            }
+impl fmt::Display for Miswiring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bit {} is miswired", self.bit)?;
 
-            prev_rem = rem.unwrap();
+        if !self.wires.is_empty() {
+            write!(f, " around {}", self.wires.iter().join(", "))?;
         }
 
-        break 'check;
+        Ok(())
     }
+}
 
-    swaps
-        .into_iter()
-        .map(|(a, b)| [a, b])
-        .flatten()
-        .sorted()
-        .join(",")
+fn solve_part1(input: &Input) -> u64 {
+    Netlist::from(input).output().expect("The circuit has a cycle")
+}
+
+fn solve_part2(input: &Input) -> Result<String, Miswiring> {
+    let fixed = Netlist::from(input).repair_adder()?;
+
+    Ok(fixed.into_iter().flat_map(|miswiring| miswiring.wires).sorted().join(","))
 }
 
 fn main() {
     let input = parse(&read(24).unwrap()).unwrap();
 
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.iter().map(String::as_str).collect_vec().as_slice() {
+        ["--dot"] => {
+            print!("{}", Netlist::from(&input).to_dot());
+            return;
+        },
+        ["--run", x, y] => {
+            match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => match Netlist::from(&input).run(x, y) {
+                    Ok(z) => println!("{} + {} -> {}", x, y, z),
+                    Err(cycle) => println!("Cycle through {}", cycle.iter().join(", ")),
+                },
+                (Err(error), _) | (_, Err(error)) => println!("Invalid operand: {}", error),
+            }
+            return;
+        },
+        _ => {},
+    }
+
     println!("{}", solve_part1(&input));
-    println!("{}", solve_part2(&input));
+
+    match solve_part2(&input) {
+        Ok(wires) => println!("{}", wires),
+        Err(miswiring) => println!("Unable to repair the adder: {}", miswiring),
+    }
 }
 
 #[cfg(test)]
@@ -313,11 +414,92 @@ x02 OR y02 -> z02
         ])}
     }
 
+    /// Ripple carry adder over `width` bits with all inputs at zero
+    fn adder(width: usize) -> Input {
+        let mut lines = Vec::new();
+
+        for prefix in ["x", "y"] {
+            lines.extend((0..width).map(|i| format!("{}{:02}: 0", prefix, i)));
+        }
+
+        lines.push(String::new());
+        lines.push("x00 XOR y00 -> z00".to_owned());
+        lines.push("x00 AND y00 -> c00".to_owned());
+
+        for i in 1..width {
+            let carry = if i + 1 == width { format!("z{:02}", width) } else { format!("c{:02}", i) };
+
+            lines.push(format!("x{:02} XOR y{:02} -> s{:02}", i, i, i));
+            lines.push(format!("x{:02} AND y{:02} -> a{:02}", i, i, i));
+            lines.push(format!("s{:02} XOR c{:02} -> z{:02}", i, i - 1, i));
+            lines.push(format!("s{:02} AND c{:02} -> t{:02}", i, i - 1, i));
+            lines.push(format!("a{:02} OR t{:02} -> {}", i, i, carry));
+        }
+
+        parse::<Input>(&(lines.join("\n") + "\n")).unwrap()
+    }
+
+    fn id(value: &str) -> Id {
+        Id(value.to_owned())
+    }
+
     #[test]
     fn test_parser() {
         assert_eq!(parse::<Input>(INPUT), Ok(parsed_input()));
     }
 
+    #[test]
+    fn test_run() {
+        let netlist = Netlist::from(&adder(6));
+
+        assert_eq!(netlist.width(), 6);
+        assert_eq!(netlist.verify_adder(), Ok(()));
+
+        for (x, y) in [(0, 0), (1, 1), (21, 42), (63, 63), (37, 50)] {
+            assert_eq!(netlist.run(x, y), Ok(x + y));
+        }
+    }
+
+    #[test]
+    fn test_cycle() {
+        let netlist = Netlist::from(&parse::<Input>("x00: 1\n\nx00 AND b -> a\na OR x00 -> b\nb XOR a -> z00\n").unwrap());
+
+        let cycle = netlist.output().unwrap_err();
+
+        assert_eq!(cycle.len(), 2);
+        assert!(cycle.contains(&id("a")) && cycle.contains(&id("b")));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = Netlist::from(&parsed_input()).to_dot();
+
+        assert!(dot.starts_with("digraph netlist {\n"));
+        assert!(dot.contains("    x00 [shape=box, label=\"x00: 1\"];\n"));
+        assert!(dot.contains("    z01 [label=\"z01\\nXOR\"];\n"));
+        assert!(dot.contains("    x01 -> z01;\n"));
+    }
+
+    #[test]
+    fn test_repair_adder() {
+        for swaps in [vec![("z02", "t02")], vec![("s03", "a03")], vec![("z04", "c04"), ("t02", "c02")], vec![("c01", "z05"), ("s03", "t04")]] {
+            let mut netlist = Netlist::from(&adder(6));
+
+            for (a, b) in &swaps {
+                netlist.swap(&id(a), &id(b));
+            }
+
+            let miswiring = netlist.verify_adder().unwrap_err();
+            let fixed = netlist.repair_adder().unwrap();
+
+            assert_eq!(fixed[0], miswiring);
+            assert_eq!(
+                fixed.iter().flat_map(|miswiring| miswiring.wires.iter().map(|wire| wire.0.as_str())).sorted().collect_vec(),
+                swaps.iter().flat_map(|(a, b)| [*a, *b]).sorted().collect_vec(),
+            );
+        }
+    }
+
     #[test]
     fn test_solve_part1() {
         assert_eq!(solve_part1(&parse::<Input>("x00: 1
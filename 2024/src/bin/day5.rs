@@ -1,4 +1,4 @@
-use advent_of_code_2024::{parser::*, read};
+use advent_of_code_2024::{graph::Graph, parser::*, read};
 use nom::{bytes::complete::tag, multi::separated_list1, sequence::separated_pair, IResult};
 
 type Page = u32;
@@ -101,34 +101,21 @@ fn solve_part2(input: &Input) -> u32 {
     input.updates.iter()
         .filter(|update| input.rules.iter().any(|rule| !rule.validate(&update.pages)))
         .map(|update| {
-            let applicable_rules: Vec<&Rule> = input.rules.iter().filter(|rule| match rule.find(&update.pages) {
-                (Some(_), Some(_)) => true,
-                _ => false,
-            }).collect();
-
-            let mut pages = update.pages.to_owned();
-
-            loop {
-                let mut changed = false;
-
-                for rule in &applicable_rules {
-                    match rule.find(&pages) {
-                        (Some(first), Some(second)) => {
-                            if first > second {
-                                pages.swap(first, second);
-                                changed = true;
-                            }
-                        },
-                        _ => panic!("Shouldn't append"),
-                    }
-                }
+            let mut rules = Graph::new();
+
+            for page in &update.pages {
+                rules.add_node(*page);
+            }
 
-                if !changed {
-                    break;
+            for rule in &input.rules {
+                if let (Some(_), Some(_)) = rule.find(&update.pages) {
+                    rules.add_edge(rule.first, rule.second, ());
                 }
             }
 
-            pages[(pages.len() - 1) / 2]
+            let pages = rules.toposort().expect("Rules shouldn't contain cycles");
+
+            *pages[(pages.len() - 1) / 2]
         })
         .sum()
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use advent_of_code_2024::{grid::{Grid, Point, Vector}, parser::*, read, util::gcd};
+use advent_of_code_2024::{grid::{Grid, Point, Vector}, number_theory::gcd, parser::*, read};
 use itertools::Itertools;
 use nom::{branch::alt, bytes::complete::tag, character::complete::none_of, combinator::{map, value}, multi::many1, sequence::terminated, IResult};
 
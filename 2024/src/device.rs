@@ -0,0 +1,230 @@
+use std::{fmt, ops::Range};
+
+use itertools::Itertools;
+
+pub type Value = u64;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Instruction {
+    Adv(u8),
+    Bxl(Value),
+    Bst(u8),
+    Jnz(usize),
+    Bxc,
+    Out(u8),
+    Bdv(u8),
+    Cdv(u8),
+}
+
+fn combo_name(operand: u8) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "a".to_owned(),
+        5 => "b".to_owned(),
+        6 => "c".to_owned(),
+        _ => "?".to_owned(),
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Adv(operand) => write!(f, "adv {}", combo_name(*operand)),
+            Instruction::Bxl(operand) => write!(f, "bxl {}", operand),
+            Instruction::Bst(operand) => write!(f, "bst {}", combo_name(*operand)),
+            Instruction::Jnz(operand) => write!(f, "jnz {}", operand * 2),
+            Instruction::Bxc => write!(f, "bxc"),
+            Instruction::Out(operand) => write!(f, "out {}", combo_name(*operand)),
+            Instruction::Bdv(operand) => write!(f, "bdv {}", combo_name(*operand)),
+            Instruction::Cdv(operand) => write!(f, "cdv {}", combo_name(*operand)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Device {
+    pub registry_a: Value,
+    pub registry_b: Value,
+    pub registry_c: Value,
+
+    pub pointer: usize,
+
+    pub program: Vec<Instruction>,
+}
+
+/// State of the device right after executing one instruction
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Step {
+    pub pointer: usize,
+    pub instruction: Instruction,
+
+    pub registry_a: Value,
+    pub registry_b: Value,
+    pub registry_c: Value,
+
+    pub output: Option<Value>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>3}: {:<6} a={} b={} c={}",
+            self.pointer * 2,
+            self.instruction.to_string(),
+            self.registry_a,
+            self.registry_b,
+            self.registry_c,
+        )?;
+
+        if let Some(output) = self.output {
+            write!(f, " out={}", output)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Device  {
+    /// Device loaded with a program given as its list of 3-bit numbers
+    pub fn new(registry_a: Value, registry_b: Value, registry_c: Value, program: &[u8]) -> Self {
+        let program = program.iter().tuples().map(|(instruction, operand)| match instruction {
+            0 => Instruction::Adv(*operand),
+            1 => Instruction::Bxl(*operand as Value),
+            2 => Instruction::Bst(*operand),
+            3 => Instruction::Jnz((*operand / 2) as usize),
+            4 => Instruction::Bxc,
+            5 => Instruction::Out(*operand),
+            6 => Instruction::Bdv(*operand),
+            7 => Instruction::Cdv(*operand),
+            _ => panic!("Invalid instruction"),
+        }).collect();
+
+        Self {
+            registry_a,
+            registry_b,
+            registry_c,
+
+            pointer: 0,
+
+            program,
+        }
+    }
+
+    pub fn step(&mut self) -> Option<Step> {
+        if self.pointer >= self.program.len() {
+            return None;
+        }
+
+        let pointer = self.pointer;
+        let (next_pointer, output) = self.execute_instruction(pointer);
+
+        self.pointer = next_pointer;
+
+        Some(Step {
+            pointer,
+            instruction: self.program[pointer].clone(),
+            registry_a: self.registry_a,
+            registry_b: self.registry_b,
+            registry_c: self.registry_c,
+            output,
+        })
+    }
+
+    pub fn trace(&mut self) -> impl Iterator<Item = Step> + '_ {
+        std::iter::from_fn(|| self.step())
+    }
+
+    fn execute_instruction(&mut self, pointer: usize) -> (usize, Option<Value>) {
+        let mut next_pointer = pointer + 1;
+        let mut output = None;
+
+        match self.program[pointer] {
+            // 0
+            Instruction::Adv(operand) => {
+                self.registry_a >>= self.combo(operand);
+            },
+            // 1
+            Instruction::Bxl(operand) => {
+                self.registry_b ^= operand;
+            },
+            // 2
+            Instruction::Bst(operand) => {
+                self.registry_b = self.combo(operand) % 8;
+            },
+            // 3
+            Instruction::Jnz(operand) => {
+                if self.registry_a != 0 {
+                    next_pointer = operand;
+                }
+            },
+            // 4
+            Instruction::Bxc => {
+                self.registry_b ^= self.registry_c;
+            },
+            // 5
+            Instruction::Out(operand) => {
+                output = Some(self.combo(operand) % 8);
+            },
+            // 6
+            Instruction::Bdv(operand) => {
+                self.registry_b = self.registry_a >> self.combo(operand);
+            },
+            // 7
+            Instruction::Cdv(operand) => {
+                self.registry_c = self.registry_a >> self.combo(operand);
+            },
+        }
+
+        (next_pointer, output)
+    }
+
+    fn combo(&self, operand: u8) -> Value {
+        match operand {
+            0 => 0,
+            1 => 1,
+            2 => 2,
+            3 => 3,
+            4 => self.registry_a,
+            5 => self.registry_b,
+            6 => self.registry_c,
+            7 => panic!("Reserved"),
+            _ => panic!("Invalid operand"),
+        }
+    }
+}
+
+impl Iterator for Device {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.trace().find_map(|step| step.output)
+    }
+}
+
+/// Listing of the program with one mnemonic per line, prefixed by its address
+pub fn disassemble(device: &Device) -> Vec<String> {
+    device.program.iter()
+        .enumerate()
+        .map(|(pointer, instruction)| format!("{:>3}: {}", pointer * 2, instruction))
+        .collect()
+}
+
+/// Steps of the program until it halts, stopping after `limit` of them as some programs never halt
+pub fn trace(device: &Device, limit: usize) -> Vec<Step> {
+    device.clone().trace().take(limit).collect()
+}
+
+/// First value output when starting with each value of register A, which is the output of one
+/// iteration for programs looping over A
+pub fn iteration_outputs(device: &Device, values: Range<Value>) -> Vec<(Value, Option<Value>)> {
+    values
+        .map(|value| {
+            let mut device = device.clone();
+
+            device.registry_a = value;
+
+            (value, device.next())
+        })
+        .collect()
+}
@@ -1,10 +1,22 @@
 use std::fs::read_to_string;
 
+pub mod device;
 pub mod dijkstra;
 pub mod geometry;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/graph.rs"]
+pub mod graph;
 pub mod grid;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/matrix.rs"]
+pub mod matrix;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/number_theory.rs"]
+pub mod number_theory;
 pub mod parser;
-pub mod util;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/rational.rs"]
+pub mod rational;
 
 pub fn read(day: u8) -> std::io::Result<String> {
     read_to_string(format!("input/day{}.txt", day))
@@ -1,10 +0,0 @@
-pub fn gcd(x: i32, y: i32) -> i32 {
-    let mut x = x;
-    let mut y = y;
-    while y != 0 {
-        let t = y;
-        y = x % y;
-        x = t;
-    }
-    x
-}
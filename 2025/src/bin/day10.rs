@@ -1,4 +1,4 @@
-use advent_of_code_2025::{dijkstra::{Edge, shortest_path}, gcd, parser::*, read};
+use advent_of_code_2025::{dijkstra::{Edge, shortest_path}, integer_programming::{minimize, Constraint}, parser::*, read};
 use derive_more::IntoIterator;
 use nom::{IResult, branch::alt, bytes::complete::tag, combinator::{map, value}, multi::{many1, separated_list1}, sequence::{delimited, tuple}};
 
@@ -105,150 +105,32 @@ fn solve_part1(input: &Input) -> u32 {
 }
 
 fn solve_part2(input: &Input) -> u16 {
-    let mut total = 0;
-
-    // fn print_matrice(matrice: &Vec<Vec<i32>>) {
-    //     for row in matrice {
-    //         for col in row {
-    //             print!("{} ", col);
-    //         }
-
-    //         print!("\n");
-    //     }
-
-    //     print!("\n");
-    // }
-
-    fn first_non_zero(vec: &Vec<i32>) -> usize {
-        vec.iter().position(|&i| i != 0).map(|position| position).unwrap_or(vec.len())
-    }
-
-    fn exact_div(a: i32, b: i32) -> i32 {
-        let div = a.div_euclid(b);
-        let rem = a.rem_euclid(b);
-
-        assert_eq!(rem, 0);
-
-        div
-    }
-
-    for (machine_index, machine) in input.into_iter().enumerate() {
-        // Building matrix
-        let mut matrix = vec![vec![0; machine.wiring_schematics.len() + 1]; machine.joltage_requirements.len()];
-
-        for (wiring_index, wiring_schematics) in machine.wiring_schematics.iter().enumerate() {
-            for &jotlage_index in wiring_schematics {
-                matrix[jotlage_index as usize][wiring_index] = 1;
-            }
-        }
-
-        for (joltage_index, &joltage_value) in machine.joltage_requirements.iter().enumerate() {
-            matrix[joltage_index][machine.wiring_schematics.len()] = joltage_value as i32;
-        }
-
-        // Diagonalization
-        for i in 0..std::cmp::min(machine.wiring_schematics.len(), machine.joltage_requirements.len()) {
-            // Sort matrix
-            matrix.sort_by_key(first_non_zero);
-
-            // Normalise matrix a bit: this is not needed but helps debuging by having cleaner matrices
-            if matrix[i][i] < 0 {
-                matrix[i] = matrix[i].iter().map(|v| -v).collect();
-            }
-
-            if matrix[i][i] > 1 {
-                let gcd = matrix[i].iter().cloned().filter(|&v| v != 0).reduce(|a, b| gcd(a.abs(), b.abs())).unwrap();
-                matrix[i] = matrix[i].iter().map(|&v| exact_div(v, gcd)).collect();
-            }
-
-            let factor_a = matrix[i][i];
-
-            if factor_a == 0 {
-                // This is maybe fine
-                continue;
-            }
-
-            for j in i+1..matrix.len() {
-                let factor_b = matrix[j][i];
-
-                if factor_b == 0 {
-                    continue;
-                }
-
-                matrix[j] = (0..machine.wiring_schematics.len() + 1).map(|col| matrix[j][col] * factor_a - matrix[i][col] * factor_b).collect();
-            }
-        }
-
-        // Remove nul rows
-        while let Some(_) = matrix.pop_if(|row| row.iter().all(|&v| v == 0)) {}
-
-        // Solving
-        let maxes: Vec<_> = machine.wiring_schematics.iter()
-            .map(|wiring_schemantic| wiring_schemantic.iter()
-                .map(|&index| machine.joltage_requirements[index as usize])
-                .min()
-                .unwrap()
-            )
-            .collect();
-
-        let mut solutions: Vec<Vec<Option<u16>>> = vec![vec![None; machine.wiring_schematics.len()]];
-
-        for row in matrix.iter().rev() {
-            let mut row = row.clone();
-            let value = row.pop().unwrap();
-            let coefs: Vec<_> = row.into_iter().enumerate().filter(|(_, v)| v != &0).collect();
-
-            let mut new_solutions = Vec::new();
-
-            for solution in solutions {
-                let mut solutions_to_test = vec![solution.clone()];
-
-                for (index, _) in &coefs {
-                    if solution[*index].is_some() {
-                        continue;
-                    }
-
-                    solutions_to_test = solutions_to_test.iter()
-                        .flat_map(|solution_to_test| (0..=maxes[*index]).map(|value| {
-                            let mut clone = solution_to_test.clone();
-
-                            clone[*index] = Some(value);
-
-                            clone
-                        }))
-                        .collect();
-                }
-
-                for solution_to_test in solutions_to_test {
-                    let mut sum = 0;
-
-                    for (index, coef) in &coefs {
-                        sum += (solution_to_test[*index].unwrap() as i32) * coef;
-                    }
-
-                    if sum == value {
-                        new_solutions.push(solution_to_test);
-                    }
-                }
-            }
-
-            solutions = new_solutions;
-        }
-
-        let min: u16 = solutions.iter()
-            .map(|solution| solution.iter()
-                .map(|count| count.unwrap())
-                .sum()
-            )
-            .min()
-            .unwrap();
-
-        println!("Machine {}: {} solutions, min={}", machine_index + 1, solutions.len(), min);
-
-        total += min;
-    }
-
-    total
+    input.into_iter()
+        .map(|machine| {
+            let constraints: Vec<Constraint> = machine.joltage_requirements.iter()
+                .enumerate()
+                .map(|(joltage_index, &joltage_value)| Constraint::new(
+                    machine.wiring_schematics.iter()
+                        .map(|wiring_schematics| wiring_schematics.contains(&(joltage_index as Light)) as i64)
+                        .collect::<Vec<_>>(),
+                    joltage_value as i64,
+                ))
+                .collect();
+
+            // A button can't be pressed more than the lowest joltage it increments
+            let maxes: Vec<i64> = machine.wiring_schematics.iter()
+                .map(|wiring_schemantic| wiring_schemantic.iter()
+                    .map(|&index| machine.joltage_requirements[index as usize] as i64)
+                    .min()
+                    .unwrap()
+                )
+                .collect();
+
+            minimize(&vec![1; maxes.len()], &constraints, &maxes)
+                .expect("Machine should be configurable")
+                .value as u16
+        })
+        .sum()
 }
 
 fn main() {
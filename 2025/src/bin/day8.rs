@@ -1,6 +1,4 @@
-use std::{collections::HashSet, hash::Hash};
-
-use advent_of_code_2025::{parser::*, read};
+use advent_of_code_2025::{parser::*, read, union_find::{minimum_spanning_tree, UnionFind}};
 use derive_more::IntoIterator;
 use itertools::Itertools;
 use nom::{IResult, bytes::complete::tag, combinator::map, sequence::tuple};
@@ -50,53 +48,27 @@ impl Parsable for Input {
     }
 }
 
-fn euclidian_distance(a: &Point, b: &Point) -> f32 {
-    ((a.0 as f32 - b.0 as f32).powi(2) + (a.1 as f32 - b.1 as f32).powi(2) + (a.2 as f32 - b.2 as f32).powi(2)).sqrt()
+fn squared_distance(a: &Point, b: &Point) -> Coordinate {
+    a.0.abs_diff(b.0).pow(2) + a.1.abs_diff(b.1).pow(2) + a.2.abs_diff(b.2).pow(2)
 }
 
-fn pairs_by_distance<'a>(input: &'a Input) -> Vec<(&'a Point, &'a Point)> {
-    input.into_iter()
+fn pairs_by_distance(input: &Input) -> Vec<(usize, usize, Coordinate)> {
+    input.0.iter()
+        .enumerate()
         .tuple_combinations()
-        .sorted_by(|(a1, b1), (a2, b2)| euclidian_distance(a1, b1).partial_cmp(&euclidian_distance(a2, b2)).unwrap())
+        .map(|((i, a), (j, b))| (i, j, squared_distance(a, b)))
+        .sorted_by_key(|(_, _, distance)| *distance)
         .collect()
 }
 
-struct Circuitry<'a>(Vec<HashSet<&'a Point>>);
-
-impl<'a> Circuitry<'a> {
-    fn new(input: &'a Input) -> Self{
-        Self(input.into_iter().map(|a| HashSet::from([a])).collect())
-    }
-
-    fn circuits(&self) -> &Vec<HashSet<&'a Point>> {
-        &self.0
-    }
-
-    fn connect(&mut self, a: &'a Point, b: &'a Point) {
-        let (connected, mut rest): (Vec<_>, Vec<_>) = self.0.iter()
-            .cloned()
-            .partition(|group| group.contains(a) || group.contains(b));
-
-        let mut group = HashSet::new();
-        for set in connected {
-            group.extend(set);
-        }
-
-        rest.push(group);
-
-        self.0 = rest;
-    }
-}
-
 fn solve_part1(input: &Input, connections: usize) -> usize {
-    let mut circuitry = Circuitry::new(input);
+    let mut circuitry = UnionFind::new(input.0.len());
 
-    for (a, b) in pairs_by_distance(input).into_iter().take(connections) {
-        circuitry.connect(a, b);
+    for (a, b, _) in pairs_by_distance(input).into_iter().take(connections) {
+        circuitry.union(a, b);
     }
 
-    circuitry.circuits().into_iter()
-        .map(|group| group.len())
+    circuitry.sizes().into_iter()
         .sorted()
         .rev()
         .take(3)
@@ -104,17 +76,11 @@ fn solve_part1(input: &Input, connections: usize) -> usize {
 }
 
 fn solve_part2(input: &Input) -> Coordinate {
-    let mut circuitry = Circuitry::new(input);
-
-    for (a, b) in pairs_by_distance(input) {
-        circuitry.connect(a, b);
-
-        if circuitry.circuits().len() == 1 {
-            return a.0 * b.0;
-        }
-    }
+    let (a, b, _) = *minimum_spanning_tree(input.0.len(), pairs_by_distance(input))
+        .last()
+        .expect("Here be dragons");
 
-    panic!("Here be dragons");
+    input.0[a].0 * input.0[b].0
 }
 
 fn main() {
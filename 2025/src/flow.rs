@@ -0,0 +1,456 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+/// Capacities and flows are widened from `u64`, so that residuals of reverse edges can't overflow
+#[derive(Clone, Debug)]
+struct FlowEdge {
+    to: usize,
+    capacity: i128,
+    flow: i128,
+    original: bool,
+}
+
+impl FlowEdge {
+    fn residual(&self) -> i128 {
+        self.capacity - self.flow
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cut<N: Eq + Hash> {
+    pub value: u64,
+    pub edges: Vec<(N, N)>,
+    pub source_side: HashSet<N>,
+    pub sink_side: HashSet<N>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlowError {
+    /// The source or the sink isn't connected to any edge
+    UnknownNode,
+    /// The source and the sink are the same node, making the flow unbounded
+    SameEndpoints,
+    /// The maximum flow exceeds `u64::MAX`
+    Overflow,
+}
+
+impl fmt::Display for FlowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowError::UnknownNode => write!(f, "node isn't part of the network"),
+            FlowError::SameEndpoints => write!(f, "source and sink are the same node"),
+            FlowError::Overflow => write!(f, "maximum flow doesn't fit in 64 bits"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FlowNetwork<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<FlowEdge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl<N> FlowNetwork<N>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+            adjacency: Vec::new(),
+        }
+    }
+
+    fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+
+        let index = self.nodes.len();
+
+        self.nodes.push(node.clone());
+        self.indices.insert(node, index);
+        self.adjacency.push(Vec::new());
+
+        index
+    }
+
+    fn add_pair(&mut self, from: N, to: N, capacity: u64, reverse_capacity: u64, reverse_original: bool) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(FlowEdge { to, capacity: capacity.into(), flow: 0, original: true });
+
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(FlowEdge { to: from, capacity: reverse_capacity.into(), flow: 0, original: reverse_original });
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, capacity: u64) {
+        self.add_pair(from, to, capacity, 0, false);
+    }
+
+    /// Adds an edge usable in both directions, which will be reported at most once in cuts.
+    pub fn add_undirected_edge(&mut self, a: N, b: N, capacity: u64) {
+        self.add_pair(a, b, capacity, capacity, true);
+    }
+
+    fn levels(&self, source: usize) -> Vec<Option<usize>> {
+        let mut levels = vec![None; self.nodes.len()];
+        let mut queue = VecDeque::from([source]);
+
+        levels[source] = Some(0);
+
+        while let Some(node) = queue.pop_front() {
+            for &edge in &self.adjacency[node] {
+                let FlowEdge { to, .. } = self.edges[edge];
+
+                if levels[to].is_none() && self.edges[edge].residual() > 0 {
+                    levels[to] = Some(levels[node].unwrap() + 1);
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        levels
+    }
+
+    fn push(&mut self, node: usize, sink: usize, limit: i128, levels: &[Option<usize>], next: &mut [usize]) -> i128 {
+        if node == sink {
+            return limit;
+        }
+
+        while next[node] < self.adjacency[node].len() {
+            let edge = self.adjacency[node][next[node]];
+            let to = self.edges[edge].to;
+            let residual = self.edges[edge].residual();
+
+            if residual > 0 && levels[to] == levels[node].map(|level| level + 1) {
+                let pushed = self.push(to, sink, limit.min(residual), levels, next);
+
+                if pushed > 0 {
+                    self.edges[edge].flow += pushed;
+                    self.edges[edge ^ 1].flow -= pushed;
+
+                    return pushed;
+                }
+            }
+
+            next[node] += 1;
+        }
+
+        0
+    }
+
+    /// Maximum flow from `source` to `sink` using Dinic's algorithm, along with the matching minimum cut.
+    /// Fails when either node is missing from the network, when they are the same, or when the
+    /// flow exceeds `u64::MAX`.
+    pub fn max_flow(&self, source: &N, sink: &N) -> Result<Cut<N>, FlowError> {
+        let source = *self.indices.get(source).ok_or(FlowError::UnknownNode)?;
+        let sink = *self.indices.get(sink).ok_or(FlowError::UnknownNode)?;
+
+        if source == sink {
+            return Err(FlowError::SameEndpoints);
+        }
+
+        let mut network = self.clone();
+        let mut value: i128 = 0;
+
+        loop {
+            let levels = network.levels(source);
+
+            if levels[sink].is_none() {
+                break;
+            }
+
+            let mut next = vec![0; network.nodes.len()];
+
+            loop {
+                let pushed = network.push(source, sink, i128::MAX, &levels, &mut next);
+
+                if pushed == 0 {
+                    break;
+                }
+
+                value += pushed;
+            }
+        }
+
+        let reachable = network.levels(source);
+
+        let mut edges = Vec::new();
+        for (from, adjacency) in network.adjacency.iter().enumerate() {
+            for &edge in adjacency {
+                let FlowEdge { to, original, .. } = network.edges[edge];
+
+                if original && reachable[from].is_some() && reachable[to].is_none() {
+                    edges.push((network.nodes[from].clone(), network.nodes[to].clone()));
+                }
+            }
+        }
+
+        let (source_side, sink_side) = split(&network.nodes, |index| reachable[index].is_some());
+
+        let value = u64::try_from(value).map_err(|_| FlowError::Overflow)?;
+
+        Ok(Cut { value, edges, source_side, sink_side })
+    }
+}
+
+impl<N> Default for FlowNetwork<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn split<N: Clone + Eq + Hash>(nodes: &[N], is_source: impl Fn(usize) -> bool) -> (HashSet<N>, HashSet<N>) {
+    let mut source_side = HashSet::new();
+    let mut sink_side = HashSet::new();
+
+    for (index, node) in nodes.iter().enumerate() {
+        if is_source(index) {
+            source_side.insert(node.clone());
+        } else {
+            sink_side.insert(node.clone());
+        }
+    }
+
+    (source_side, sink_side)
+}
+
+#[derive(Eq, PartialEq)]
+struct Connectivity {
+    weight: u64,
+    node: usize,
+}
+
+impl Ord for Connectivity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.weight.cmp(&other.weight)
+    }
+}
+
+impl PartialOrd for Connectivity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Minimum cut splitting an undirected weighted graph in two, using the Stoer–Wagner algorithm.
+/// Returns `None` when the graph has less than two nodes.
+pub fn global_min_cut<N>(edges: impl IntoIterator<Item = (N, N, u64)>) -> Option<Cut<N>>
+where
+    N: Clone + Eq + Hash,
+{
+    let mut nodes = Vec::new();
+    let mut indices = HashMap::new();
+    let mut original = Vec::new();
+
+    for (a, b, weight) in edges {
+        let [a, b] = [a, b].map(|node| {
+            *indices.entry(node.clone()).or_insert_with(|| {
+                nodes.push(node);
+                nodes.len() - 1
+            })
+        });
+
+        original.push((a, b, weight));
+    }
+
+    if nodes.len() < 2 {
+        return None;
+    }
+
+    let mut adjacency: Vec<HashMap<usize, u64>> = vec![HashMap::new(); nodes.len()];
+    for &(a, b, weight) in &original {
+        if a != b {
+            *adjacency[a].entry(b).or_default() += weight;
+            *adjacency[b].entry(a).or_default() += weight;
+        }
+    }
+
+    let mut members: Vec<Vec<usize>> = (0..nodes.len()).map(|node| vec![node]).collect();
+    let mut active: Vec<usize> = (0..nodes.len()).collect();
+    let mut best: Option<(u64, Vec<usize>)> = None;
+
+    while active.len() > 1 {
+        let mut weights: HashMap<usize, u64> = HashMap::new();
+        let mut added = HashSet::new();
+        let mut heap = BinaryHeap::from([Connectivity { weight: 0, node: active[0] }]);
+        let mut order = Vec::new();
+
+        while order.len() < active.len() {
+            let node = match heap.pop() {
+                Some(Connectivity { weight, node }) => {
+                    if added.contains(&node) || weights.get(&node).copied().unwrap_or(0) != weight {
+                        continue;
+                    }
+
+                    node
+                },
+                // Disconnected graph, pick any remaining node
+                None => *active.iter().find(|node| !added.contains(*node)).unwrap(),
+            };
+
+            added.insert(node);
+            order.push(node);
+
+            for (&neighbor, &weight) in &adjacency[node] {
+                if !added.contains(&neighbor) {
+                    let total = weights.entry(neighbor).or_default();
+                    *total += weight;
+                    heap.push(Connectivity { weight: *total, node: neighbor });
+                }
+            }
+        }
+
+        let last = order[order.len() - 1];
+        let previous = order[order.len() - 2];
+        let cut_of_phase = weights.get(&last).copied().unwrap_or(0);
+
+        if best.as_ref().is_none_or(|(weight, _)| cut_of_phase < *weight) {
+            best = Some((cut_of_phase, members[last].clone()));
+        }
+
+        let merged = std::mem::take(&mut adjacency[last]);
+        for (neighbor, weight) in merged {
+            adjacency[neighbor].remove(&last);
+
+            if neighbor != previous {
+                *adjacency[previous].entry(neighbor).or_default() += weight;
+                *adjacency[neighbor].entry(previous).or_default() += weight;
+            }
+        }
+
+        let moved = std::mem::take(&mut members[last]);
+        members[previous].extend(moved);
+        active.retain(|node| *node != last);
+    }
+
+    let (value, side) = best?;
+    let side: HashSet<usize> = side.into_iter().collect();
+
+    let edges = original.iter()
+        .filter(|(a, b, _)| side.contains(a) != side.contains(b))
+        .map(|(a, b, _)| (nodes[*a].clone(), nodes[*b].clone()))
+        .collect();
+
+    let (source_side, sink_side) = split(&nodes, |index| side.contains(&index));
+
+    Some(Cut { value, edges, source_side, sink_side })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIRES: [(&str, &[&str]); 13] = [
+        ("jqt", &["rhn", "xhk", "nvd"]),
+        ("rsh", &["frs", "pzl", "lsr"]),
+        ("xhk", &["hfx"]),
+        ("cmg", &["qnr", "nvd", "lhk", "bvb"]),
+        ("rhn", &["xhk", "bvb", "hfx"]),
+        ("bvb", &["xhk", "hfx"]),
+        ("pzl", &["lsr", "hfx", "nvd"]),
+        ("qnr", &["nvd"]),
+        ("ntq", &["jqt", "hfx", "bvb", "xhk"]),
+        ("nvd", &["lhk"]),
+        ("lsr", &["lhk"]),
+        ("rzs", &["qnr", "cmg", "lsr", "rsh"]),
+        ("frs", &["qnr", "lhk", "lsr"]),
+    ];
+
+    fn wires() -> impl Iterator<Item = (&'static str, &'static str)> {
+        WIRES.into_iter().flat_map(|(from, tos)| tos.iter().map(move |to| (from, *to)))
+    }
+
+    fn normalized(mut edges: Vec<(&'static str, &'static str)>) -> Vec<(&'static str, &'static str)> {
+        for edge in edges.iter_mut() {
+            if edge.0 > edge.1 {
+                *edge = (edge.1, edge.0);
+            }
+        }
+
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_max_flow() {
+        let mut network = FlowNetwork::new();
+
+        for (a, b) in wires() {
+            network.add_undirected_edge(a, b, 1);
+        }
+
+        let cut = network.max_flow(&"cmg", &"xhk").unwrap();
+
+        assert_eq!(cut.value, 3);
+        assert_eq!(normalized(cut.edges), vec![("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+        assert_eq!(cut.source_side.len() * cut.sink_side.len(), 54);
+    }
+
+    #[test]
+    fn test_directed_max_flow() {
+        let mut network = FlowNetwork::new();
+
+        network.add_edge('s', 'a', 10);
+        network.add_edge('s', 'b', 5);
+        network.add_edge('a', 'b', 15);
+        network.add_edge('a', 't', 5);
+        network.add_edge('b', 't', 10);
+
+        let cut = network.max_flow(&'s', &'t').unwrap();
+
+        assert_eq!(cut.value, 15);
+        assert_eq!(cut.source_side, HashSet::from(['s']));
+    }
+
+    #[test]
+    fn test_max_flow_errors() {
+        let mut network = FlowNetwork::new();
+
+        network.add_edge('s', 't', 10);
+
+        assert_eq!(network.max_flow(&'s', &'x').unwrap_err(), FlowError::UnknownNode);
+        assert_eq!(network.max_flow(&'x', &'t').unwrap_err(), FlowError::UnknownNode);
+        assert_eq!(network.max_flow(&'s', &'s').unwrap_err(), FlowError::SameEndpoints);
+
+        network.add_edge('s', 't', u64::MAX);
+
+        assert_eq!(network.max_flow(&'s', &'t').unwrap_err(), FlowError::Overflow);
+    }
+
+    #[test]
+    fn test_max_flow_large_capacities() {
+        let mut network = FlowNetwork::new();
+
+        network.add_undirected_edge('s', 'a', u64::MAX);
+        network.add_undirected_edge('a', 'b', u64::MAX - 1);
+        network.add_edge('b', 't', u64::MAX);
+        network.add_edge('a', 't', 1);
+
+        let cut = network.max_flow(&'s', &'t').unwrap();
+
+        assert_eq!(cut.value, u64::MAX);
+        assert_eq!(network.max_flow(&'t', &'s').unwrap().value, 0);
+    }
+
+    #[test]
+    fn test_global_min_cut() {
+        let cut = global_min_cut(wires().map(|(a, b)| (a, b, 1))).unwrap();
+
+        assert_eq!(cut.value, 3);
+        assert_eq!(normalized(cut.edges), vec![("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+        assert_eq!(cut.source_side.len() * cut.sink_side.len(), 54);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::matrix::Matrix;
+use crate::rational::Rational;
+
+/// Linear equality constraint `sum(coefficients[i] * x[i]) = value`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Constraint {
+    pub coefficients: Vec<i64>,
+    pub value: i64,
+}
+
+impl Constraint {
+    pub fn new(coefficients: impl Into<Vec<i64>>, value: i64) -> Self {
+        Self { coefficients: coefficients.into(), value }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Solution {
+    pub value: i64,
+    pub variables: Vec<i64>,
+}
+
+struct Search {
+    /// Contribution of one unit of each free variable to each variable and to the objective
+    directions: Vec<Vec<Rational>>,
+    free_bounds: Vec<i64>,
+    bounds: Vec<i64>,
+    /// Smallest and largest contribution of the free variables from a given depth onward
+    suffix_min: Vec<Vec<Rational>>,
+    suffix_max: Vec<Vec<Rational>>,
+    best: Option<(Rational, Vec<i64>)>,
+}
+
+impl Search {
+    fn explore(&mut self, depth: usize, current: Vec<Rational>) {
+        let objective = current.len() - 1;
+
+        for (index, value) in current.iter().enumerate() {
+            let min = *value + self.suffix_min[depth][index];
+            let max = *value + self.suffix_max[depth][index];
+
+            if index == objective {
+                if let Some((best, _)) = &self.best {
+                    if min >= *best {
+                        return;
+                    }
+                }
+            } else if max < Rational::ZERO || min > Rational::from(self.bounds[index]) {
+                return;
+            }
+        }
+
+        if depth == self.directions.len() {
+            if current.iter().all(Rational::is_integer) {
+                let variables = current[..objective].iter().map(|value| value.numerator() as i64).collect();
+
+                self.best = Some((current[objective], variables));
+            }
+
+            return;
+        }
+
+        for value in 0..=self.free_bounds[depth] {
+            let next = current.iter()
+                .zip(&self.directions[depth])
+                .map(|(current, direction)| *current + Rational::from(value) * *direction)
+                .collect();
+
+            self.explore(depth + 1, next);
+        }
+    }
+}
+
+/// Minimises `objective` over integer variables in `0..=bounds[i]` satisfying every constraint,
+/// using a branch and bound over the free variables left by the elimination of the constraints.
+/// `None` if no such variables exist, which includes a negative bound.
+pub fn minimize(objective: &[i64], constraints: &[Constraint], bounds: &[i64]) -> Option<Solution> {
+    let size = objective.len();
+
+    assert_eq!(bounds.len(), size);
+    assert!(constraints.iter().all(|constraint| constraint.coefficients.len() == size));
+
+    if bounds.iter().any(|&bound| bound < 0) {
+        return None;
+    }
+
+    let constraints = if constraints.is_empty() {
+        vec![Constraint::new(vec![0; size], 0)]
+    } else {
+        constraints.to_vec()
+    };
+
+    let matrix: Matrix<Rational> = Matrix::from(
+        constraints.iter().map(|constraint| constraint.coefficients.clone()).collect::<Vec<_>>(),
+    );
+    let values: Vec<Rational> = constraints.iter().map(|constraint| constraint.value.into()).collect();
+
+    let space = matrix.solve(&values)?;
+
+    let with_objective = |vector: &[Rational]| -> Vec<Rational> {
+        let value = vector.iter().zip(objective).map(|(value, &weight)| *value * Rational::from(weight)).sum();
+
+        vector.iter().copied().chain([value]).collect()
+    };
+
+    let base = with_objective(&space.particular);
+    let directions: Vec<Vec<Rational>> = space.null_space.iter().map(|vector| with_objective(vector)).collect();
+    let free_bounds: Vec<i64> = space.free_variables.iter().map(|&index| bounds[index]).collect();
+
+    let mut suffix_min = vec![vec![Rational::ZERO; size + 1]; directions.len() + 1];
+    let mut suffix_max = vec![vec![Rational::ZERO; size + 1]; directions.len() + 1];
+
+    for depth in (0..directions.len()).rev() {
+        for index in 0..=size {
+            let extent = directions[depth][index] * Rational::from(free_bounds[depth]);
+
+            suffix_min[depth][index] = suffix_min[depth + 1][index] + extent.min(Rational::ZERO);
+            suffix_max[depth][index] = suffix_max[depth + 1][index] + extent.max(Rational::ZERO);
+        }
+    }
+
+    let mut search = Search {
+        directions,
+        free_bounds,
+        bounds: bounds.to_vec(),
+        suffix_min,
+        suffix_max,
+        best: None,
+    };
+
+    search.explore(0, base);
+
+    search.best.map(|(value, variables)| Solution { value: value.numerator() as i64, variables })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize() {
+        // x + y = 10, x - y = 2
+        let constraints = [Constraint::new([1, 1], 10), Constraint::new([1, -1], 2)];
+
+        assert_eq!(minimize(&[1, 1], &constraints, &[10, 10]), Some(Solution { value: 10, variables: vec![6, 4] }));
+        assert_eq!(minimize(&[1, 1], &constraints, &[5, 10]), None);
+    }
+
+    #[test]
+    fn test_minimize_free_variables() {
+        // x + y + z = 6 with z <= 2, cheapest with as much z as allowed
+        let constraints = [Constraint::new([1, 1, 1], 6)];
+
+        assert_eq!(minimize(&[3, 2, 1], &constraints, &[6, 6, 2]), Some(Solution { value: 10, variables: vec![0, 4, 2] }));
+
+        // Negative weights push the variables to their bounds instead of leaving them unbounded
+        assert_eq!(minimize(&[-1, -2], &[], &[3, 4]), Some(Solution { value: -11, variables: vec![3, 4] }));
+        assert_eq!(minimize(&[1, 1], &[], &[3, 4]), Some(Solution { value: 0, variables: vec![0, 0] }));
+    }
+
+    #[test]
+    fn test_minimize_infeasible() {
+        // No integer solution to 2x + 2y = 5
+        assert_eq!(minimize(&[1, 1], &[Constraint::new([2, 2], 5)], &[5, 5]), None);
+        // Contradictory equalities
+        assert_eq!(minimize(&[1, 1], &[Constraint::new([1, 1], 1), Constraint::new([1, 1], 2)], &[5, 5]), None);
+        assert_eq!(minimize(&[1], &[Constraint::new([1], 1)], &[-1]), None);
+    }
+}
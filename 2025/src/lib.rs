@@ -2,20 +2,21 @@ use std::fs::read_to_string;
 
 pub mod counter;
 pub mod dijkstra;
+pub mod flow;
 pub mod grid;
+pub mod integer_programming;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/matrix.rs"]
+pub mod matrix;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/number_theory.rs"]
+pub mod number_theory;
 pub mod parser;
+// Shared between the yearly crates, see `shared/` at the root of the repository
+#[path = "../../shared/rational.rs"]
+pub mod rational;
+pub mod union_find;
 
 pub fn read(day: u8) -> std::io::Result<String> {
     read_to_string(format!("input/day{}.txt", day))
 }
-
-pub fn gcd(x: i32, y: i32) -> i32 {
-    let mut x = x;
-    let mut y = y;
-    while y != 0 {
-        let t = y;
-        y = x % y;
-        x = t;
-    }
-    x
-}
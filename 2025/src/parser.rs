@@ -67,8 +67,44 @@ impl Parsable for u64 {
     }
 }
 
+impl Parsable for u128 {
+    fn parser(input: &str) -> IResult<&str, Self> {
+        nom::character::complete::u128(input)
+    }
+}
+
+impl Parsable for i128 {
+    fn parser(input: &str) -> IResult<&str, Self> {
+        nom::character::complete::i128(input)
+    }
+}
+
 impl Parsable for char {
     fn parser(input: &str) -> IResult<&str, Self> {
         nom::character::complete::anychar(input)
     }
 }
+
+#[cfg(feature = "bigint")]
+mod bigint {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::digit1;
+    use nom::combinator::{map_res, opt, recognize};
+    use nom::sequence::pair;
+    use nom::IResult;
+    use num_bigint::{BigInt, BigUint};
+
+    use super::Parsable;
+
+    impl Parsable for BigUint {
+        fn parser(input: &str) -> IResult<&str, Self> {
+            map_res(digit1, str::parse)(input)
+        }
+    }
+
+    impl Parsable for BigInt {
+        fn parser(input: &str) -> IResult<&str, Self> {
+            map_res(recognize(pair(opt(tag("-")), digit1)), str::parse)(input)
+        }
+    }
+}
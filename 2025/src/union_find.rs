@@ -0,0 +1,172 @@
+use itertools::Itertools;
+
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut current = element;
+
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning false if they were already merged.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let a = self.find(a);
+        let b = self.find(b);
+
+        if a == b {
+            return false;
+        }
+
+        let (large, small) = if self.sizes[a] >= self.sizes[b] { (a, b) } else { (b, a) };
+
+        self.parents[small] = large;
+        self.sizes[large] += self.sizes[small];
+        self.count -= 1;
+
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+
+        self.sizes[root]
+    }
+
+    pub fn sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&element| self.parents[element] == element)
+            .map(|root| self.sizes[root])
+            .collect()
+    }
+
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        (0..self.len())
+            .into_group_map_by(|&element| self.find(element))
+            .into_values()
+            .collect()
+    }
+
+    /// Same as `groups` but with each element replaced by its label.
+    pub fn labelled_groups<'a, T>(&mut self, labels: &'a [T]) -> Vec<Vec<&'a T>> {
+        self.groups()
+            .into_iter()
+            .map(|group| group.into_iter().map(|element| &labels[element]).collect())
+            .collect()
+    }
+}
+
+/// Edges of a minimum spanning forest of the `len` nodes graph, in the order they were added.
+pub fn minimum_spanning_tree<W: Ord + Copy>(
+    len: usize,
+    edges: impl IntoIterator<Item = (usize, usize, W)>,
+) -> Vec<(usize, usize, W)> {
+    let mut union_find = UnionFind::new(len);
+    let mut tree = Vec::new();
+
+    for (a, b, weight) in edges.into_iter().sorted_by_key(|(_, _, weight)| *weight) {
+        if union_find.union(a, b) {
+            tree.push((a, b, weight));
+
+            if union_find.count() == 1 {
+                break;
+            }
+        }
+    }
+
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut union_find = UnionFind::new(6);
+
+        assert!(union_find.union(0, 1));
+        assert!(union_find.union(2, 3));
+        assert!(union_find.union(1, 3));
+        assert!(!union_find.union(0, 2));
+
+        assert_eq!(union_find.len(), 6);
+        assert_eq!(union_find.count(), 3);
+        assert!(union_find.connected(0, 3));
+        assert!(!union_find.connected(0, 4));
+        assert_eq!(union_find.find(0), union_find.find(2));
+        assert_eq!(union_find.size(3), 4);
+        assert_eq!(union_find.size(5), 1);
+        assert_eq!(union_find.sizes().into_iter().sorted().collect::<Vec<_>>(), vec![1, 1, 4]);
+    }
+
+    #[test]
+    fn test_groups() {
+        let mut union_find = UnionFind::new(5);
+
+        union_find.union(4, 0);
+        union_find.union(1, 3);
+
+        let mut groups: Vec<Vec<usize>> = union_find.groups();
+        groups.sort();
+
+        assert_eq!(groups, vec![vec![0, 4], vec![1, 3], vec![2]]);
+
+        let mut labelled = union_find.labelled_groups(&["a", "b", "c", "d", "e"]);
+        labelled.sort();
+
+        assert_eq!(labelled, vec![vec![&"a", &"e"], vec![&"b", &"d"], vec![&"c"]]);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 3), (2, 3, 2), (1, 3, 5), (4, 5, 1)];
+
+        // Lightest edges first, ties kept in their original order, skipping the ones closing a cycle
+        assert_eq!(minimum_spanning_tree(6, edges), vec![(1, 2, 1), (4, 5, 1), (2, 3, 2), (0, 2, 3)]);
+
+        assert_eq!(minimum_spanning_tree(3, [(0, 0, 0), (0, 1, 1), (1, 2, 2), (0, 2, 3)]), vec![(0, 1, 1), (1, 2, 2)]);
+        assert_eq!(minimum_spanning_tree::<u32>(0, []), vec![]);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+
+/// A sequence of states that repeats itself every `period` steps once `start` steps have been made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Smallest step reaching the same state as step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// Value at step `n` of a quantity growing by the same amount on each period, `value`
+    /// being only called for steps before the end of the first period.
+    pub fn extrapolate(&self, n: usize, value: impl Fn(usize) -> i64) -> i64 {
+        if n < self.start + self.period {
+            return value(n);
+        }
+
+        let periods = ((n - self.start) / self.period) as i64;
+        let growth = value(self.start + self.period) - value(self.start);
+
+        value(self.equivalent_step(n)) + periods * growth
+    }
+}
+
+pub fn find_cycle<S, K>(initial: &S, step: impl Fn(&S) -> S, key: impl Fn(&S) -> K) -> Cycle
+where
+    S: Clone,
+    K: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut current = initial.clone();
+
+    for index in 0.. {
+        match seen.entry(key(&current)) {
+            Entry::Occupied(entry) => {
+                let start = *entry.get();
+
+                return Cycle { start, period: index - start };
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(index);
+            },
+        }
+
+        current = step(&current);
+    }
+
+    unreachable!()
+}
+
+pub fn find_cycle_floyd<S, K>(initial: &S, step: impl Fn(&S) -> S, key: impl Fn(&S) -> K) -> Cycle
+where
+    S: Clone,
+    K: PartialEq,
+{
+    let mut tortoise = step(initial);
+    let mut hare = step(&tortoise);
+
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+
+    while key(&tortoise) != key(&hare) {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle { start, period }
+}
+
+pub fn find_cycle_brent<S, K>(initial: &S, step: impl Fn(&S) -> S, key: impl Fn(&S) -> K) -> Cycle
+where
+    S: Clone,
+    K: PartialEq,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+
+    while key(&tortoise) != key(&hare) {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial.clone();
+
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// State after `n` steps, skipping over the repetitions once a cycle has been found.
+pub fn nth_state<S, K>(initial: &S, step: impl Fn(&S) -> S, key: impl Fn(&S) -> K, n: usize) -> S
+where
+    S: Clone,
+    K: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut current = initial.clone();
+
+    for index in 0..n {
+        if let Some(&start) = seen.get(&key(&current)) {
+            let cycle = Cycle { start, period: index - start };
+
+            return states.swap_remove(cycle.equivalent_step(n));
+        }
+
+        seen.insert(key(&current), index);
+        states.push(current.clone());
+        current = step(&current);
+    }
+
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts up to 9 then jumps back to 4: a tail of 4 states then a loop of 6
+    fn step(state: &u32) -> u32 {
+        if *state < 9 { state + 1 } else { 4 }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let cycle = Cycle { start: 4, period: 6 };
+
+        assert_eq!(find_cycle(&0, step, |&state| state), cycle);
+        assert_eq!(find_cycle_floyd(&0, step, |&state| state), cycle);
+        assert_eq!(find_cycle_brent(&0, step, |&state| state), cycle);
+
+        let loop_only = Cycle { start: 0, period: 5 };
+
+        assert_eq!(find_cycle(&0, |state| (state + 1) % 5, |&state| state), loop_only);
+        assert_eq!(find_cycle_floyd(&0, |state| (state + 1) % 5, |&state| state), loop_only);
+        assert_eq!(find_cycle_brent(&0, |state| (state + 1) % 5, |&state| state), loop_only);
+    }
+
+    #[test]
+    fn test_key() {
+        // The step counter never repeats, only the key does
+        let step = |&(state, steps): &(u32, usize)| (step(&state), steps + 1);
+
+        assert_eq!(find_cycle(&(0, 0), step, |&(state, _)| state), Cycle { start: 4, period: 6 });
+        assert_eq!(nth_state(&(0, 0), step, |&(state, _)| state, 101), (5, 5));
+    }
+
+    #[test]
+    fn test_equivalent_step() {
+        let cycle = Cycle { start: 4, period: 6 };
+
+        assert_eq!(cycle.equivalent_step(3), 3);
+        assert_eq!(cycle.equivalent_step(10), 4);
+        assert_eq!(cycle.equivalent_step(101), 5);
+        assert_eq!(cycle.extrapolate(100, |n| 2 * n as i64), 200);
+        assert_eq!(cycle.extrapolate(7, |n| 2 * n as i64), 14);
+    }
+
+    #[test]
+    fn test_nth_state() {
+        assert_eq!(nth_state(&0, step, |&state| state, 0), 0);
+        assert_eq!(nth_state(&0, step, |&state| state, 3), 3);
+        assert_eq!(nth_state(&0, step, |&state| state, 101), 5);
+        assert_eq!(nth_state(&0, step, |&state| state, 1_000_000_000), 4);
+    }
+}
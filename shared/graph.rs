@@ -0,0 +1,662 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+#[derive(Clone, Debug)]
+pub struct Graph<N, E = ()> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, E)>>,
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N, E)>) -> Self {
+        let mut graph = Self::new();
+
+        for (from, to, weight) in edges {
+            graph.add_edge(from, to, weight);
+        }
+
+        graph
+    }
+
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+
+        let index = self.nodes.len();
+
+        self.nodes.push(node.clone());
+        self.indices.insert(node, index);
+        self.edges.push(Vec::new());
+
+        index
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: E) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N, &E)> {
+        self.edges.iter().enumerate().flat_map(move |(from, edges)| {
+            edges.iter().map(move |(to, weight)| (&self.nodes[from], &self.nodes[*to], weight))
+        })
+    }
+
+    pub fn contains_node(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn contains_edge(&self, from: &N, to: &N) -> bool {
+        match (self.indices.get(from), self.indices.get(to)) {
+            (Some(&from), Some(&to)) => self.edges[from].iter().any(|(index, _)| *index == to),
+            _ => false,
+        }
+    }
+
+    pub fn neighbors(&self, node: &N) -> impl Iterator<Item = (&N, &E)> {
+        self.indices
+            .get(node)
+            .map(|&index| self.edges[index].as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|(to, weight)| (&self.nodes[*to], weight))
+    }
+
+    /// Nodes ordered so that every edge goes from an earlier node to a later one, or
+    /// one of the cycles preventing such an order.
+    pub fn toposort(&self) -> Result<Vec<&N>, Vec<&N>> {
+        let mut in_degrees = vec![0; self.nodes.len()];
+
+        for edges in &self.edges {
+            for (to, _) in edges {
+                in_degrees[*to] += 1;
+            }
+        }
+
+        let mut queue: VecDeque<usize> = (0..self.nodes.len()).filter(|&index| in_degrees[index] == 0).collect();
+        let mut order = Vec::new();
+
+        while let Some(index) = queue.pop_front() {
+            order.push(&self.nodes[index]);
+
+            for (to, _) in &self.edges[index] {
+                in_degrees[*to] -= 1;
+
+                if in_degrees[*to] == 0 {
+                    queue.push_back(*to);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle().expect("Remaining nodes should contain a cycle"))
+        }
+    }
+
+    pub fn find_cycle(&self) -> Option<Vec<&N>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            Active,
+            Done,
+        }
+
+        let mut marks = vec![Mark::New; self.nodes.len()];
+
+        for root in 0..self.nodes.len() {
+            if marks[root] != Mark::New {
+                continue;
+            }
+
+            let mut stack = vec![(root, 0)];
+            marks[root] = Mark::Active;
+
+            while let Some((index, next)) = stack.last_mut() {
+                let index = *index;
+
+                if let Some((to, _)) = self.edges[index].get(*next) {
+                    *next += 1;
+
+                    match marks[*to] {
+                        Mark::New => {
+                            marks[*to] = Mark::Active;
+                            stack.push((*to, 0));
+                        },
+                        Mark::Active => {
+                            let start = stack.iter().position(|(index, _)| index == to).unwrap();
+
+                            return Some(stack[start..].iter().map(|(index, _)| &self.nodes[*index]).collect());
+                        },
+                        Mark::Done => {},
+                    }
+                } else {
+                    marks[index] = Mark::Done;
+                    stack.pop();
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Strongly connected components using Tarjan's algorithm, in reverse topological order.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&N>> {
+        struct Tarjan<'a, N, E> {
+            graph: &'a Graph<N, E>,
+            counter: usize,
+            indices: Vec<Option<usize>>,
+            low_links: Vec<usize>,
+            on_stack: Vec<bool>,
+            stack: Vec<usize>,
+            components: Vec<Vec<&'a N>>,
+        }
+
+        impl<N, E> Tarjan<'_, N, E> {
+            fn enter(&mut self, node: usize) {
+                self.indices[node] = Some(self.counter);
+                self.low_links[node] = self.counter;
+                self.counter += 1;
+                self.stack.push(node);
+                self.on_stack[node] = true;
+            }
+
+            /// Depth first search from `root`, with an explicit stack of the nodes being visited
+            /// along with their next edge so that long chains can't overflow the call stack
+            fn visit(&mut self, root: usize) {
+                let graph = self.graph;
+                let mut path = vec![(root, 0)];
+
+                self.enter(root);
+
+                while let Some((node, next)) = path.last_mut() {
+                    let node = *node;
+
+                    if let Some((to, _)) = graph.edges[node].get(*next) {
+                        *next += 1;
+
+                        match self.indices[*to] {
+                            None => {
+                                self.enter(*to);
+                                path.push((*to, 0));
+                            },
+                            Some(index) if self.on_stack[*to] => {
+                                self.low_links[node] = self.low_links[node].min(index);
+                            },
+                            Some(_) => {},
+                        }
+
+                        continue;
+                    }
+
+                    path.pop();
+
+                    if let Some(&(parent, _)) = path.last() {
+                        self.low_links[parent] = self.low_links[parent].min(self.low_links[node]);
+                    }
+
+                    if Some(self.low_links[node]) == self.indices[node] {
+                        let mut component = Vec::new();
+
+                        loop {
+                            let member = self.stack.pop().unwrap();
+                            self.on_stack[member] = false;
+                            component.push(&graph.nodes[member]);
+
+                            if member == node {
+                                break;
+                            }
+                        }
+
+                        self.components.push(component);
+                    }
+                }
+            }
+        }
+
+        let mut tarjan = Tarjan {
+            graph: self,
+            counter: 0,
+            indices: vec![None; self.nodes.len()],
+            low_links: vec![0; self.nodes.len()],
+            on_stack: vec![false; self.nodes.len()],
+            stack: Vec::new(),
+            components: Vec::new(),
+        };
+
+        for node in 0..self.nodes.len() {
+            if tarjan.indices[node].is_none() {
+                tarjan.visit(node);
+            }
+        }
+
+        tarjan.components
+    }
+
+    pub fn dfs(&self, start: &N) -> Vec<&N> {
+        let Some(&start) = self.indices.get(start) else {
+            return Vec::new();
+        };
+
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![start];
+        let mut order = Vec::new();
+
+        while let Some(index) = stack.pop() {
+            if visited[index] {
+                continue;
+            }
+
+            visited[index] = true;
+            order.push(&self.nodes[index]);
+
+            for (to, _) in self.edges[index].iter().rev() {
+                if !visited[*to] {
+                    stack.push(*to);
+                }
+            }
+        }
+
+        order
+    }
+
+    pub fn bfs(&self, start: &N) -> Vec<&N> {
+        let Some(&start) = self.indices.get(start) else {
+            return Vec::new();
+        };
+
+        let mut visited = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::from([start]);
+        let mut order = Vec::new();
+
+        visited[start] = true;
+
+        while let Some(index) = queue.pop_front() {
+            order.push(&self.nodes[index]);
+
+            for (to, _) in &self.edges[index] {
+                if !visited[*to] {
+                    visited[*to] = true;
+                    queue.push_back(*to);
+                }
+            }
+        }
+
+        order
+    }
+
+    /// All paths from `from` to `to`, where `can_visit` is asked before entering a node
+    /// with the number of times each node has already been visited on the current path.
+    pub fn paths(&self, from: &N, to: &N, can_visit: impl Fn(&N, &HashMap<&N, usize>) -> bool) -> Vec<Vec<&N>> {
+        fn walk<'a, N: Eq + Hash, E>(
+            graph: &'a Graph<N, E>,
+            current: usize,
+            goal: usize,
+            path: &mut Vec<usize>,
+            visits: &mut HashMap<&'a N, usize>,
+            can_visit: &impl Fn(&N, &HashMap<&N, usize>) -> bool,
+            paths: &mut Vec<Vec<&'a N>>,
+        ) {
+            if current == goal {
+                paths.push(path.iter().map(|index| &graph.nodes[*index]).collect());
+
+                return;
+            }
+
+            for (next, _) in &graph.edges[current] {
+                let node = &graph.nodes[*next];
+
+                if !can_visit(node, visits) {
+                    continue;
+                }
+
+                path.push(*next);
+                *visits.entry(node).or_default() += 1;
+
+                walk(graph, *next, goal, path, visits, can_visit, paths);
+
+                *visits.get_mut(node).unwrap() -= 1;
+                path.pop();
+            }
+        }
+
+        let (Some(&from), Some(&to)) = (self.indices.get(from), self.indices.get(to)) else {
+            return Vec::new();
+        };
+
+        let mut paths = Vec::new();
+        let mut visits = HashMap::from([(&self.nodes[from], 1)]);
+
+        walk(self, from, to, &mut vec![from], &mut visits, &can_visit, &mut paths);
+
+        paths
+    }
+
+    pub fn simple_paths(&self, from: &N, to: &N) -> Vec<Vec<&N>> {
+        self.paths(from, to, |node, visits| visits.get(node).copied().unwrap_or(0) == 0)
+    }
+
+    pub fn subgraph(&self, keep: impl Fn(&N) -> bool) -> Self
+    where
+        E: Clone,
+    {
+        let mut graph = Self::new();
+
+        for node in self.nodes.iter().filter(|node| keep(node)) {
+            graph.add_node(node.clone());
+        }
+
+        for (from, to, weight) in self.edges() {
+            if keep(from) && keep(to) {
+                graph.add_edge(from.clone(), to.clone(), weight.clone());
+            }
+        }
+
+        graph
+    }
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Clone + Eq + Hash,
+    E: Clone,
+{
+    pub fn from_undirected_edges(edges: impl IntoIterator<Item = (N, N, E)>) -> Self {
+        let mut graph = Self::new();
+
+        for (a, b, weight) in edges {
+            graph.add_edge(a.clone(), b.clone(), weight.clone());
+            graph.add_edge(b, a, weight);
+        }
+
+        graph
+    }
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Clone + Eq + Hash + Ord,
+{
+    fn undirected_adjacency(&self) -> Vec<HashSet<usize>> {
+        let mut adjacency = vec![HashSet::new(); self.nodes.len()];
+
+        for (from, edges) in self.edges.iter().enumerate() {
+            for (to, _) in edges {
+                if from != *to {
+                    adjacency[from].insert(*to);
+                    adjacency[*to].insert(from);
+                }
+            }
+        }
+
+        adjacency
+    }
+
+    fn to_set(&self, indices: impl IntoIterator<Item = usize>) -> BTreeSet<&N> {
+        indices.into_iter().map(|index| &self.nodes[index]).collect()
+    }
+
+    pub fn triangles(&self) -> Vec<BTreeSet<&N>> {
+        self.k_cliques(3)
+    }
+
+    /// Every set of `k` nodes that are all connected to each other, edges being
+    /// considered undirected.
+    pub fn k_cliques(&self, k: usize) -> Vec<BTreeSet<&N>> {
+        fn extend(
+            adjacency: &[HashSet<usize>],
+            clique: &mut Vec<usize>,
+            candidates: Vec<usize>,
+            k: usize,
+            cliques: &mut Vec<Vec<usize>>,
+        ) {
+            if clique.len() == k {
+                cliques.push(clique.clone());
+
+                return;
+            }
+
+            for (position, &candidate) in candidates.iter().enumerate() {
+                let next_candidates = candidates[position + 1..]
+                    .iter()
+                    .copied()
+                    .filter(|other| adjacency[candidate].contains(other))
+                    .collect::<Vec<_>>();
+
+                if clique.len() + 1 + next_candidates.len() < k {
+                    continue;
+                }
+
+                clique.push(candidate);
+                extend(adjacency, clique, next_candidates, k, cliques);
+                clique.pop();
+            }
+        }
+
+        if k == 0 {
+            return vec![BTreeSet::new()];
+        }
+
+        let adjacency = self.undirected_adjacency();
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by(|a, b| self.nodes[*a].cmp(&self.nodes[*b]));
+
+        let mut cliques = Vec::new();
+        extend(&adjacency, &mut Vec::new(), order, k, &mut cliques);
+
+        cliques.into_iter().map(|clique| self.to_set(clique)).collect()
+    }
+
+    /// All maximal cliques using Bron–Kerbosch with pivoting, edges being considered undirected.
+    pub fn maximal_cliques(&self) -> Vec<BTreeSet<&N>> {
+        fn bron_kerbosch(
+            adjacency: &[HashSet<usize>],
+            clique: &mut Vec<usize>,
+            mut candidates: HashSet<usize>,
+            mut excluded: HashSet<usize>,
+            cliques: &mut Vec<Vec<usize>>,
+        ) {
+            if candidates.is_empty() && excluded.is_empty() {
+                cliques.push(clique.clone());
+
+                return;
+            }
+
+            let pivot = candidates
+                .union(&excluded)
+                .max_by_key(|node| adjacency[**node].intersection(&candidates).count())
+                .copied()
+                .unwrap();
+
+            let branches: Vec<usize> = candidates.difference(&adjacency[pivot]).copied().collect();
+
+            for node in branches {
+                clique.push(node);
+                bron_kerbosch(
+                    adjacency,
+                    clique,
+                    candidates.intersection(&adjacency[node]).copied().collect(),
+                    excluded.intersection(&adjacency[node]).copied().collect(),
+                    cliques,
+                );
+                clique.pop();
+
+                candidates.remove(&node);
+                excluded.insert(node);
+            }
+        }
+
+        let adjacency = self.undirected_adjacency();
+        let mut cliques = Vec::new();
+
+        bron_kerbosch(&adjacency, &mut Vec::new(), (0..self.nodes.len()).collect(), HashSet::new(), &mut cliques);
+
+        cliques.into_iter().map(|clique| self.to_set(clique)).collect()
+    }
+
+    /// The largest clique, ties being broken by the smallest set.
+    pub fn maximum_clique(&self) -> BTreeSet<&N> {
+        self.maximal_cliques()
+            .into_iter()
+            .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+            .unwrap_or_default()
+    }
+}
+
+impl<N> Graph<N, ()>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (N, N)>) -> Self {
+        Self::from_edges(pairs.into_iter().map(|(from, to)| (from, to, ())))
+    }
+
+    pub fn from_undirected_pairs(pairs: impl IntoIterator<Item = (N, N)>) -> Self {
+        Self::from_undirected_edges(pairs.into_iter().map(|(a, b)| (a, b, ())))
+    }
+}
+
+impl<N, E> Default for Graph<N, E>
+where
+    N: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caves() -> Graph<&'static str> {
+        Graph::from_undirected_pairs([
+            ("start", "A"),
+            ("start", "b"),
+            ("A", "c"),
+            ("A", "b"),
+            ("b", "d"),
+            ("A", "end"),
+            ("b", "end"),
+        ])
+    }
+
+    fn is_small(cave: &str) -> bool {
+        cave.chars().all(char::is_lowercase)
+    }
+
+    #[test]
+    fn test_toposort() {
+        let graph = Graph::from_pairs([(3, 1), (1, 2), (3, 2), (2, 4)]);
+
+        assert_eq!(graph.toposort(), Ok(vec![&3, &1, &2, &4]));
+    }
+
+    #[test]
+    fn test_toposort_cycle() {
+        let graph = Graph::from_pairs([(0, 1), (1, 2), (2, 3), (3, 1)]);
+
+        assert_eq!(graph.toposort(), Err(vec![&1, &2, &3]));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = Graph::from_pairs([(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (5, 4)]);
+
+        let mut components: Vec<Vec<i32>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|component| {
+                let mut component: Vec<i32> = component.into_iter().copied().collect();
+                component.sort();
+                component
+            })
+            .collect();
+        components.sort();
+
+        assert_eq!(components, vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components_long_chain() {
+        let graph = Graph::from_pairs((0..100_000).map(|node| (node, node + 1)).chain([(100_000, 0)]));
+
+        assert_eq!(graph.strongly_connected_components().iter().map(Vec::len).collect::<Vec<_>>(), vec![100_001]);
+
+        let chain = Graph::from_pairs((0..100_000).map(|node| (node, node + 1)));
+        let components = chain.strongly_connected_components();
+
+        assert_eq!(components.len(), 100_001);
+        assert_eq!(components[0], vec![&100_000]);
+    }
+
+    #[test]
+    fn test_traversals() {
+        let graph = Graph::from_pairs([(0, 1), (0, 2), (1, 3), (2, 3)]);
+
+        assert_eq!(graph.dfs(&0), vec![&0, &1, &3, &2]);
+        assert_eq!(graph.bfs(&0), vec![&0, &1, &2, &3]);
+    }
+
+    #[test]
+    fn test_paths() {
+        let graph = caves();
+
+        let paths = graph.paths(&"start", &"end", |cave, visits| {
+            !is_small(cave) || visits.get(cave).copied().unwrap_or(0) == 0
+        });
+
+        assert_eq!(paths.len(), 10);
+    }
+
+    #[test]
+    fn test_paths_visiting_one_small_cave_twice() {
+        let graph = caves();
+
+        let paths = graph.paths(&"start", &"end", |cave, visits| {
+            if *cave == "start" {
+                return false;
+            }
+
+            if !is_small(cave) || visits.get(cave).copied().unwrap_or(0) == 0 {
+                return true;
+            }
+
+            !visits.iter().any(|(cave, count)| is_small(cave) && *count > 1)
+        });
+
+        assert_eq!(paths.len(), 36);
+    }
+}
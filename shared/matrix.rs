@@ -0,0 +1,307 @@
+use super::rational::Rational;
+
+#[derive(Clone, Hash, PartialEq, Eq, Debug)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.data[row * self.cols + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) {
+        self.data[row * self.cols + col] = value;
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.cols {
+            self.data.swap(a * self.cols + col, b * self.cols + col);
+        }
+    }
+}
+
+impl<T> Matrix<T> where T: Clone + Default {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![T::default(); rows * cols],
+        }
+    }
+}
+
+impl<T, U> From<Vec<Vec<U>>> for Matrix<T> where U: Into<T> {
+    fn from(value: Vec<Vec<U>>) -> Self {
+        let rows = value.len();
+        let cols = value.first().map_or(0, Vec::len);
+
+        Self {
+            rows,
+            cols,
+            data: value.into_iter().flatten().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Matrix<Rational> {
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::new(size, size);
+
+        for index in 0..size {
+            matrix.set(index, index, Rational::ONE);
+        }
+
+        matrix
+    }
+
+    /// Matrix with `column` appended as its last column
+    pub fn augmented(&self, column: &[Rational]) -> Self {
+        assert_eq!(column.len(), self.rows);
+
+        let mut data = Vec::with_capacity(self.rows * (self.cols + 1));
+
+        for (row, value) in column.iter().enumerate() {
+            data.extend_from_slice(self.row(row));
+            data.push(*value);
+        }
+
+        Self { rows: self.rows, cols: self.cols + 1, data }
+    }
+
+    fn eliminate(&mut self, reduced: bool) -> (Vec<usize>, Rational) {
+        let mut pivots = Vec::new();
+        let mut determinant = Rational::ONE;
+        let mut row = 0;
+
+        for col in 0..self.cols {
+            if row == self.rows {
+                break;
+            }
+
+            let Some(pivot_row) = (row..self.rows).find(|&candidate| !self.get(candidate, col).is_zero()) else {
+                determinant = Rational::ZERO;
+
+                continue;
+            };
+
+            if pivot_row != row {
+                self.swap_rows(pivot_row, row);
+                determinant = -determinant;
+            }
+
+            let pivot = *self.get(row, col);
+            determinant *= pivot;
+
+            if reduced {
+                for c in col..self.cols {
+                    self.set(row, c, *self.get(row, c) / pivot);
+                }
+            }
+
+            let targets: Vec<usize> = if reduced { (0..self.rows).collect() } else { (row + 1..self.rows).collect() };
+
+            for target in targets {
+                if target == row {
+                    continue;
+                }
+
+                let factor = *self.get(target, col) / *self.get(row, col);
+
+                if factor.is_zero() {
+                    continue;
+                }
+
+                for c in col..self.cols {
+                    let value = *self.get(target, c) - factor * *self.get(row, c);
+                    self.set(target, c, value);
+                }
+            }
+
+            pivots.push(col);
+            row += 1;
+        }
+
+        (pivots, determinant)
+    }
+
+    pub fn row_echelon(&self) -> Self {
+        let mut matrix = self.clone();
+
+        matrix.eliminate(false);
+
+        matrix
+    }
+
+    /// Reduced row echelon form along with the pivot column of each non zero row
+    pub fn rref(&self) -> (Self, Vec<usize>) {
+        let mut matrix = self.clone();
+
+        let (pivots, _) = matrix.eliminate(true);
+
+        (matrix, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().eliminate(false).0.len()
+    }
+
+    pub fn determinant(&self) -> Option<Rational> {
+        if self.rows != self.cols {
+            return None;
+        }
+
+        let mut matrix = self.clone();
+        let (pivots, determinant) = matrix.eliminate(false);
+
+        if pivots.len() < self.rows {
+            Some(Rational::ZERO)
+        } else {
+            Some(determinant)
+        }
+    }
+
+    /// Every solution of `self * x = rhs`, or `None` if the system is inconsistent
+    pub fn solve(&self, rhs: &[Rational]) -> Option<SolutionSpace> {
+        let (reduced, pivots) = self.augmented(rhs).rref();
+
+        if pivots.last() == Some(&self.cols) {
+            return None;
+        }
+
+        let free_variables: Vec<usize> = (0..self.cols).filter(|col| !pivots.contains(col)).collect();
+
+        let mut particular = vec![Rational::ZERO; self.cols];
+        for (row, &col) in pivots.iter().enumerate() {
+            particular[col] = *reduced.get(row, self.cols);
+        }
+
+        let null_space = free_variables.iter()
+            .map(|&free| {
+                let mut vector = vec![Rational::ZERO; self.cols];
+                vector[free] = Rational::ONE;
+
+                for (row, &col) in pivots.iter().enumerate() {
+                    vector[col] = -*reduced.get(row, free);
+                }
+
+                vector
+            })
+            .collect();
+
+        Some(SolutionSpace { particular, null_space, pivots, free_variables })
+    }
+}
+
+/// Solutions of a linear system, written as `particular + sum(t_i * null_space[i])`
+/// with one parameter `t_i` per free variable
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SolutionSpace {
+    pub particular: Vec<Rational>,
+    pub null_space: Vec<Vec<Rational>>,
+    pub pivots: Vec<usize>,
+    pub free_variables: Vec<usize>,
+}
+
+impl SolutionSpace {
+    pub fn is_unique(&self) -> bool {
+        self.null_space.is_empty()
+    }
+
+    pub fn point(&self, parameters: &[Rational]) -> Vec<Rational> {
+        assert_eq!(parameters.len(), self.null_space.len());
+
+        let mut point = self.particular.clone();
+
+        for (parameter, vector) in parameters.iter().zip(&self.null_space) {
+            for (value, direction) in point.iter_mut().zip(vector) {
+                *value += *parameter * *direction;
+            }
+        }
+
+        point
+    }
+
+    /// The point for the given parameters if all its coordinates are integers
+    pub fn integer_point(&self, parameters: &[Rational]) -> Option<Vec<i128>> {
+        self.point(parameters).iter().map(Rational::to_integer).collect()
+    }
+
+    /// The solution if it is unique and made of integers
+    pub fn integer_solution(&self) -> Option<Vec<i128>> {
+        if !self.is_unique() {
+            return None;
+        }
+
+        self.integer_point(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: Vec<Vec<i64>>) -> Matrix<Rational> {
+        Matrix::from(rows)
+    }
+
+    fn rationals(values: &[i64]) -> Vec<Rational> {
+        values.iter().map(|&value| Rational::from(value)).collect()
+    }
+
+    #[test]
+    fn test_rref() {
+        let (reduced, pivots) = matrix(vec![vec![1, 2, -1, -4], vec![2, 3, -1, -11], vec![-2, 0, -3, 22]]).rref();
+
+        assert_eq!(reduced, matrix(vec![vec![1, 0, 0, -8], vec![0, 1, 0, 1], vec![0, 0, 1, -2]]));
+        assert_eq!(pivots, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_rank_and_determinant() {
+        assert_eq!(matrix(vec![vec![1, 2], vec![2, 4]]).rank(), 1);
+        assert_eq!(matrix(vec![vec![1, 2], vec![2, 4]]).determinant(), Some(Rational::ZERO));
+        assert_eq!(matrix(vec![vec![0, 2, 1], vec![3, 1, 0], vec![1, 1, 1]]).determinant(), Some(Rational::from(-4)));
+        assert_eq!(matrix(vec![vec![1, 2, 3]]).determinant(), None);
+    }
+
+    #[test]
+    fn test_solve_unique() {
+        let solution = matrix(vec![vec![94, 22], vec![34, 67]]).solve(&rationals(&[8400, 5400])).unwrap();
+
+        assert_eq!(solution.integer_solution(), Some(vec![80, 40]));
+
+        let solution = matrix(vec![vec![26, 67], vec![66, 21]]).solve(&rationals(&[12748, 12176])).unwrap();
+
+        assert!(solution.is_unique());
+        assert_eq!(solution.integer_solution(), None);
+    }
+
+    #[test]
+    fn test_solve_underdetermined() {
+        let solution = matrix(vec![vec![1, 1, 0], vec![0, 1, 1]]).solve(&rationals(&[3, 5])).unwrap();
+
+        assert_eq!(solution.free_variables, vec![2]);
+        assert_eq!(solution.point(&rationals(&[1])), rationals(&[-1, 4, 1]));
+        assert_eq!(solution.integer_point(&[Rational::new(1, 2)]), None);
+    }
+
+    #[test]
+    fn test_solve_inconsistent() {
+        assert_eq!(matrix(vec![vec![1, 1], vec![2, 2]]).solve(&rationals(&[1, 3])), None);
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+pub trait Integer:
+    Clone
+    + Debug
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn abs(&self) -> Self;
+}
+
+pub trait SignedInteger: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_unsigned {
+    ($($type:ty),*) => {
+        $(
+            impl Integer for $type {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn abs(&self) -> Self {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($type:ty),*) => {
+        $(
+            impl Integer for $type {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn abs(&self) -> Self {
+                    <$type>::abs(*self)
+                }
+            }
+
+            impl SignedInteger for $type {}
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+#[cfg(feature = "bigint")]
+mod bigint {
+    use num_bigint::{BigInt, BigUint, Sign};
+
+    use super::{Integer, SignedInteger};
+
+    impl Integer for BigUint {
+        fn zero() -> Self {
+            BigUint::ZERO
+        }
+
+        fn one() -> Self {
+            BigUint::from(1u8)
+        }
+
+        fn abs(&self) -> Self {
+            self.clone()
+        }
+    }
+
+    impl Integer for BigInt {
+        fn zero() -> Self {
+            BigInt::ZERO
+        }
+
+        fn one() -> Self {
+            BigInt::from(1u8)
+        }
+
+        fn abs(&self) -> Self {
+            match self.sign() {
+                Sign::Minus => -self,
+                _ => self.clone(),
+            }
+        }
+    }
+
+    impl SignedInteger for BigInt {}
+}
+
+/// Greatest common divisor, always positive for non zero inputs
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let mut a = a.abs();
+    let mut b = b.abs();
+
+    while b != T::zero() {
+        let remainder = a % b.clone();
+
+        a = b;
+        b = remainder;
+    }
+
+    a
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::zero() || b == T::zero() {
+        return T::zero();
+    }
+
+    (a.clone() / gcd(a, b.clone()) * b).abs()
+}
+
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), gcd)
+}
+
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::one(), lcm)
+}
+
+/// Euclidean remainder, always in `0..modulus` for a positive modulus
+pub fn modulo<T: Integer>(value: T, modulus: T) -> T {
+    let remainder = value % modulus.clone();
+
+    if remainder < T::zero() {
+        remainder + modulus.abs()
+    } else {
+        remainder
+    }
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y = g` with `g` the gcd of `a` and `b`
+pub fn extended_gcd<T: SignedInteger>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while r != T::zero() {
+        let quotient = old_r.clone() / r.clone();
+
+        (old_r, r) = (r.clone(), old_r - quotient.clone() * r);
+        (old_x, x) = (x.clone(), old_x - quotient.clone() * x);
+        (old_y, y) = (y.clone(), old_y - quotient * y);
+    }
+
+    if old_r < T::zero() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+pub fn mod_inverse<T: SignedInteger>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(modulo(a, modulus.clone()), modulus.clone());
+
+    if g != T::one() {
+        return None;
+    }
+
+    Some(modulo(x, modulus))
+}
+
+/// `base` to the power of `exponent` modulo `modulus`, `modulus` squared must fit in `T`
+pub fn mod_pow<T: Integer>(base: T, exponent: T, modulus: T) -> T {
+    let two = T::one() + T::one();
+
+    let mut result = T::one() % modulus.clone();
+    let mut base = modulo(base, modulus.clone());
+    let mut exponent = exponent;
+
+    while exponent > T::zero() {
+        if exponent.clone() % two.clone() == T::one() {
+            result = result * base.clone() % modulus.clone();
+        }
+
+        base = base.clone() * base % modulus.clone();
+        exponent = exponent / two.clone();
+    }
+
+    result
+}
+
+/// Solves the system `x ≡ remainder (mod modulus)` for each `(remainder, modulus)` pair, moduli
+/// don't need to be coprime. Returns the smallest non negative solution along with the modulus
+/// of the combined congruence, or `None` when the system has no solution.
+pub fn chinese_remainder<T: SignedInteger>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let mut result = (T::zero(), T::one());
+
+    for (remainder, modulus) in congruences {
+        let (current, current_modulus) = result;
+        let remainder = modulo(remainder, modulus.clone());
+
+        let (g, p, _) = extended_gcd(current_modulus.clone(), modulus.clone());
+        let difference = remainder - current.clone();
+
+        if difference.clone() % g.clone() != T::zero() {
+            return None;
+        }
+
+        let step = modulus / g.clone();
+        let factor = modulo(difference / g % step.clone() * modulo(p, step.clone()), step.clone());
+        let combined_modulus = current_modulus.clone() * step;
+
+        result = (modulo(current + current_modulus * factor, combined_modulus.clone()), combined_modulus);
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(4i32, -6), 2);
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+        assert_eq!(lcm_all([4u128, 6, 10]), 60);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!(extended_gcd(240i64, 46), (2, -9, 47));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3i32, 11), Some(4));
+        assert_eq!(mod_inverse(-3i32, 11), Some(7));
+        assert_eq!(mod_inverse(4i32, 10), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(4u64, 13, 497), 445);
+        assert_eq!(mod_pow(7i128, 0, 1), 0);
+        assert_eq!(mod_pow(2u128, 127, u64::MAX as u128), 9223372036854775808);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint() {
+        use num_bigint::BigInt;
+
+        let big = |value: &str| value.parse::<BigInt>().unwrap();
+
+        assert_eq!(gcd(big("-123456789012345678901234567890"), big("987654321098765432109876543210")), big("9000000000900000000090"));
+        assert_eq!(
+            chinese_remainder([(big("2"), big("3")), (big("3"), big("5")), (big("2"), big("7"))]),
+            Some((big("23"), big("105"))),
+        );
+    }
+
+    #[test]
+    fn test_chinese_remainder() {
+        assert_eq!(chinese_remainder([(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(chinese_remainder([(3i64, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(chinese_remainder([(1i64, 4), (2, 6)]), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! Recognition of the block letters some puzzles draw. The 2021 and 2022 crates have no common
+//! grid type, so a grid of pixels is given as a slice of rows, which any row-major grid can
+//! provide without copying its pixels.
+
+use std::fmt;
+
+const SMALL_GLYPHS: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const LARGE_GLYPHS: [(char, &str); 15] = [
+    ('A', "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#"),
+    ('B', "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####."),
+    ('C', ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####."),
+    ('E', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######"),
+    ('F', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+    ('G', ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#"),
+    ('H', "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#"),
+    ('J', "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###.."),
+    ('K', "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#"),
+    ('L', "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######"),
+    ('N', "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#"),
+    ('P', "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+    ('R', "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#"),
+    ('X', "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#"),
+    ('Z', "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######"),
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OcrError {
+    /// The lit pixels don't span the height of any known font
+    Height(usize),
+    /// Glyph not matching any letter of the font, drawn with `#` and `.`
+    Glyph(String),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Height(height) => write!(f, "no font is {} pixels high", height),
+            OcrError::Glyph(glyph) => write!(f, "unrecognised glyph:\n{}", glyph),
+        }
+    }
+}
+
+/// Decodes the letters drawn by the lit pixels, given row by row
+pub fn recognize(rows: &[impl AsRef<[bool]>]) -> Result<String, OcrError> {
+    let lit_rows: Vec<&[bool]> = rows.iter()
+        .map(AsRef::as_ref)
+        .skip_while(|row| !row.contains(&true))
+        .collect();
+    let height = lit_rows.iter().rposition(|row| row.contains(&true)).map_or(0, |last| last + 1);
+    let rows = &lit_rows[..height];
+
+    let glyphs = match height {
+        0 => return Ok(String::new()),
+        6 => &SMALL_GLYPHS[..],
+        10 => &LARGE_GLYPHS[..],
+        _ => return Err(OcrError::Height(height)),
+    };
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let is_lit = |row: &[bool], col: usize| row.get(col).copied().unwrap_or(false);
+    let is_blank_column = |col: usize| rows.iter().all(|row| !is_lit(row, col));
+
+    let mut text = String::new();
+    let mut col = 0;
+
+    while col < width {
+        if is_blank_column(col) {
+            col += 1;
+            continue;
+        }
+
+        let end = (col..width).find(|&end| is_blank_column(end)).unwrap_or(width);
+
+        let glyph = rows.iter()
+            .map(|row| (col..end).map(|c| if is_lit(row, c) { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match glyphs.iter().find(|(_, pattern)| *pattern == glyph) {
+            Some((letter, _)) => text.push(*letter),
+            None => return Err(OcrError::Glyph(glyph)),
+        }
+
+        col = end;
+    }
+
+    Ok(text)
+}
+
+/// Decodes the letters drawn by the lit `(x, y)` points
+pub fn recognize_points(points: impl IntoIterator<Item = (usize, usize)>) -> Result<String, OcrError> {
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+
+    for (x, y) in points {
+        if rows.len() <= y {
+            rows.resize(y + 1, Vec::new());
+        }
+        if rows[y].len() <= x {
+            rows[y].resize(x + 1, false);
+        }
+
+        rows[y][x] = true;
+    }
+
+    recognize(&rows)
+}
+
+/// Decodes the letters drawn with `#` characters
+pub fn recognize_str(input: &str) -> Result<String, OcrError> {
+    let rows: Vec<Vec<bool>> = input.lines().map(|line| line.chars().map(|c| c == '#').collect()).collect();
+
+    recognize(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognize_small() {
+        let screen = "\
+###..#..#.###..####.####..##..###..####.
+#..#.#..#.#..#.#....#....#..#.#..#....#.
+#..#.####.###..###..###..#....#..#...#..
+###..#..#.#..#.#....#....#.##.###...#...
+#....#..#.#..#.#....#....#..#.#.#..#....
+#....#..#.###..####.####..###.#..#.####.";
+
+        assert_eq!(recognize_str(screen), Ok("PHBEEGRZ".to_owned()));
+
+        let pixels: Vec<bool> = screen.lines().flat_map(|line| line.chars().map(|c| c == '#')).collect();
+        let rows: Vec<&[bool]> = pixels.chunks(40).collect();
+
+        assert_eq!(recognize(&rows), Ok("PHBEEGRZ".to_owned()));
+    }
+
+    #[test]
+    fn test_recognize_large() {
+        let points = LARGE_GLYPHS[..3].iter()
+            .enumerate()
+            .flat_map(|(index, (_, glyph))| {
+                glyph.lines().enumerate().flat_map(move |(y, line)| {
+                    line.chars().enumerate().filter(|(_, c)| *c == '#').map(move |(x, _)| (index * 8 + x, y + 2))
+                })
+            });
+
+        assert_eq!(recognize_points(points), Ok("ABC".to_owned()));
+    }
+
+    #[test]
+    fn test_recognize_errors() {
+        assert_eq!(recognize_str("#####\n#...#\n#...#\n#...#\n#####\n"), Err(OcrError::Height(5)));
+        assert_eq!(recognize_str("##.\n##.\n##.\n##.\n##.\n##."), Err(OcrError::Glyph("##\n##\n##\n##\n##\n##".to_owned())));
+        assert_eq!(recognize_str("...\n...\n"), Ok(String::new()));
+    }
+}